@@ -0,0 +1,443 @@
+//! Synthesizes EDIDs, e.g. for virtual displays, from a few properties and a list of modes.
+
+use std::slice;
+
+use super::cta::{self, CtaExtension, DataBlock, HdrStaticMetadata, ShortAudioDescriptor, ShortVideoDescriptor};
+use super::cvt::cvt_timing;
+use super::*;
+
+/// Bytes available to the data blocks and detailed timings of a CTA-861 extension block.
+const CTA_CAPACITY: usize = 123;
+
+/// A mode as (width, height, refresh rate in Hz).
+type Mode = (u16, u16, u16);
+
+/// Builds an EDID 1.4 for a digital display. The preferred mode comes first as a detailed timing,
+/// the other modes are listed as standard timings when they can be, as detailed timings
+/// otherwise. Detailed timings and data blocks which don't fit in the base block go to CTA-861
+/// extension blocks, and the range limits cover all modes. Timings are computed with CVT.
+#[derive(Debug, Clone)]
+pub struct EdidBuilder {
+	vendor: [char; 3],
+	product: u16,
+	serial: u32,
+	date: ManufactureDate,
+	name: Option<String>,
+	size: Option<(u16, u16)>,
+	reduced_blanking: bool,
+	preferred_mode: Mode,
+	modes: Vec<Mode>,
+	vics: Vec<u8>,
+	audio: Vec<ShortAudioDescriptor>,
+	hdr_static_metadata: Option<HdrStaticMetadata>,
+}
+
+impl Default for EdidBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl EdidBuilder {
+	/// Starts with vendor `XXX`, no name nor size, and a 1920x1080 preferred mode at 60 Hz with
+	/// reduced blanking.
+	pub fn new() -> Self {
+		EdidBuilder {
+			vendor: ['X', 'X', 'X'],
+			product: 0,
+			serial: 0,
+			date: ManufactureDate::Manufactured { week: None, year: 2024 },
+			name: None,
+			size: None,
+			reduced_blanking: true,
+			preferred_mode: (1920, 1080, 60),
+			modes: Vec::new(),
+			vics: Vec::new(),
+			audio: Vec::new(),
+			hdr_static_metadata: None,
+		}
+	}
+
+	/// Three uppercase letters of the manufacturer ID.
+	pub fn vendor(mut self, vendor: [char; 3]) -> Self {
+		self.vendor = vendor;
+		self
+	}
+
+	pub fn product(mut self, product: u16) -> Self {
+		self.product = product;
+		self
+	}
+
+	pub fn serial(mut self, serial: u32) -> Self {
+		self.serial = serial;
+		self
+	}
+
+	/// Manufacture date or model year, from 2006 onwards.
+	pub fn date(mut self, date: ManufactureDate) -> Self {
+		self.date = date;
+		self
+	}
+
+	/// Product name, up to 13 characters.
+	pub fn name(mut self, name: &str) -> Self {
+		self.name = Some(name.to_string());
+		self
+	}
+
+	/// Physical size of the screen in millimeters.
+	pub fn size(mut self, width: u16, height: u16) -> Self {
+		self.size = Some((width, height));
+		self
+	}
+
+	/// Whether the timings use CVT reduced blanking, which is the default.
+	pub fn reduced_blanking(mut self, reduced_blanking: bool) -> Self {
+		self.reduced_blanking = reduced_blanking;
+		self
+	}
+
+	pub fn preferred_mode(mut self, width: u16, height: u16, refresh_rate: u16) -> Self {
+		self.preferred_mode = (width, height, refresh_rate);
+		self
+	}
+
+	/// Adds a mode besides the preferred one.
+	pub fn mode(mut self, width: u16, height: u16, refresh_rate: u16) -> Self {
+		self.modes.push((width, height, refresh_rate));
+		self
+	}
+
+	/// Adds CTA-861 video formats by their VIC.
+	pub fn vics(mut self, vics: &[u8]) -> Self {
+		self.vics.extend_from_slice(vics);
+		self
+	}
+
+	/// Adds a CTA-861 audio format, which also sets basic audio support.
+	pub fn audio(mut self, sad: ShortAudioDescriptor) -> Self {
+		self.audio.push(sad);
+		self
+	}
+
+	pub fn hdr_static_metadata(mut self, hdr: HdrStaticMetadata) -> Self {
+		self.hdr_static_metadata = Some(hdr);
+		self
+	}
+
+	fn timing(&self, (width, height, refresh_rate): Mode) -> DetailedTiming {
+		let mut timing = cvt_timing(width, height, refresh_rate as f64, self.reduced_blanking);
+		if let Some((width, height)) = self.size {
+			timing.horizontal_size = width;
+			timing.vertical_size = height;
+		}
+		timing
+	}
+
+	fn data_blocks(&self) -> Vec<DataBlock> {
+		let mut data_blocks = Vec::new();
+		for vics in self.vics.chunks(31) {
+			let svds = vics.iter().map(|&vic| ShortVideoDescriptor { vic, native: false, ycbcr420: false });
+			data_blocks.push(DataBlock::Video(svds.collect()));
+		}
+		for sads in self.audio.chunks(10) {
+			data_blocks.push(DataBlock::Audio(sads.to_vec()));
+		}
+		if let Some(hdr) = self.hdr_static_metadata {
+			data_blocks.push(DataBlock::HdrStaticMetadata(hdr));
+		}
+		data_blocks
+	}
+
+	/// Builds the EDID structure, which `encode` turns into bytes.
+	pub fn edid(&self) -> EDID {
+		let preferred = self.timing(self.preferred_mode);
+		let mut timings = vec![preferred];
+		let mut standard_timings = [None; 8];
+		let mut detailed_timings = Vec::new();
+		let mut standard = 0;
+		for &mode in &self.modes {
+			if mode == self.preferred_mode {
+				continue;
+			}
+			let timing = self.timing(mode);
+			timings.push(timing);
+			match standard_timing_for(mode) {
+				Some(st) if standard_timings.contains(&Some(st)) => {}
+				Some(st) if standard < standard_timings.len() => {
+					standard_timings[standard] = Some(st);
+					standard += 1;
+				}
+				_ if !detailed_timings.contains(&timing) => detailed_timings.push(timing),
+				_ => {}
+			}
+		}
+
+		// The preferred timing, range limits and name come first, other timings fill the free slots
+		let mut descriptors = vec![Descriptor::DetailedTiming(preferred)];
+		let free = 2 - self.name.is_some() as usize;
+		let overflow = detailed_timings.split_off(free.min(detailed_timings.len()));
+		descriptors.extend(detailed_timings.into_iter().map(Descriptor::DetailedTiming));
+		descriptors.push(Descriptor::RangeLimits(self.range_limits(&timings)));
+		if let Some(ref name) = self.name {
+			descriptors.push(Descriptor::ProductName(name.clone()));
+		}
+
+		let (width, height) = self.size.unwrap_or((0, 0));
+		EDID {
+			header: Header {
+				vendor: self.vendor,
+				product: self.product,
+				serial: self.serial,
+				date: self.date,
+				version: 1,
+				revision: 4,
+			},
+			display: Display {
+				video_input: VideoInput::Digital(DigitalInput {
+					color_bit_depth: Some(8),
+					interface: DigitalInterface::Undefined,
+				}),
+				size: match ((width + 5) / 10, (height + 5) / 10) {
+					(0, _) | (_, 0) => ScreenSize::Undefined,
+					(w, h) => ScreenSize::Physical { width: w.min(255) as u8, height: h.min(255) as u8 },
+				},
+				gamma: Some(2.2),
+				features: Features {
+					dpms_standby: false,
+					dpms_suspend: false,
+					dpms_active_off: false,
+					color: ColorSupport::Encodings(ColorEncodings::empty()),
+					srgb_default: true,
+					preferred_timing_mode: true,
+					continuous_frequency: false,
+				},
+			},
+			chromaticity: Chromaticity {
+				red: ChromaticityPoint { x: 0.640, y: 0.330 },
+				green: ChromaticityPoint { x: 0.300, y: 0.600 },
+				blue: ChromaticityPoint { x: 0.150, y: 0.060 },
+				white: ChromaticityPoint { x: 0.3127, y: 0.3290 },
+			},
+			established_timings: EstablishedTimings::empty(),
+			standard_timings,
+			descriptors,
+			extensions: cta_extensions(self.data_blocks(), overflow, !self.audio.is_empty())
+				.into_iter()
+				.map(Extension::Cta)
+				.collect(),
+		}
+	}
+
+	/// Builds and encodes the EDID. Fails with `Error::BlockOverflow` when a mode can't be
+	/// represented, like one whose pixel clock is above the 655.35 MHz of a detailed timing.
+	pub fn build(&self) -> Result<Vec<u8>, Error> {
+		encode(&self.edid())
+	}
+
+	// Range limits covering all timings, which also allow other CVT timings in between
+	fn range_limits(&self, timings: &[DetailedTiming]) -> RangeLimits {
+		let total = |active: u16, blanking: u16| (active + blanking) as f64;
+		let rates = timings.iter().map(|t| {
+			let h_rate = t.pixel_clock as f64 / total(t.horizontal_active_pixels, t.horizontal_blanking_pixels);
+			(h_rate * 1000.0 / total(t.vertical_active_lines, t.vertical_blanking_lines), h_rate)
+		});
+		let (mut min_v, mut max_v, mut min_h, mut max_h) = (f64::INFINITY, 0.0f64, f64::INFINITY, 0.0f64);
+		for (v_rate, h_rate) in rates {
+			min_v = min_v.min(v_rate);
+			max_v = max_v.max(v_rate);
+			min_h = min_h.min(h_rate);
+			max_h = max_h.max(h_rate);
+		}
+		let max_clock = timings.iter().map(|t| t.pixel_clock).max().unwrap_or(0);
+		let max_pixel_clock = max_clock.div_ceil(10_000) * 10;
+
+		let (width, height, refresh_rate) = self.preferred_mode;
+		RangeLimits {
+			min_vertical_rate: (min_v.floor() as u16).clamp(1, 510),
+			max_vertical_rate: (max_v.ceil() as u16).clamp(1, 510),
+			min_horizontal_rate: (min_h.floor() as u16).clamp(1, 510),
+			max_horizontal_rate: (max_h.ceil() as u16).clamp(1, 510),
+			vertical_rate_offset: rate_offset_for(min_v.floor(), max_v.ceil()),
+			horizontal_rate_offset: rate_offset_for(min_h.floor(), max_h.ceil()),
+			max_pixel_clock: max_pixel_clock.min(2550) as u16,
+			video_timing_support: VideoTimingSupport::Cvt(CvtSupport {
+				version: (1, 1),
+				pixel_clock_precision: ((max_pixel_clock * 1000).saturating_sub(max_clock) / 250).min(63) as u8,
+				max_active_pixels: None,
+				supported_aspect_ratios: CvtAspectRatios::all(),
+				preferred_aspect_ratio: cvt_aspect_ratio(width, height),
+				reduced_blanking: self.reduced_blanking,
+				standard_blanking: !self.reduced_blanking,
+				scaling: CvtScaling::empty(),
+				preferred_refresh_rate: refresh_rate.min(255) as u8,
+			}),
+		}
+	}
+}
+
+fn rate_offset_for(min: f64, max: f64) -> RateOffset {
+    match (min > 255.0, max > 255.0) {
+        (true, _) => RateOffset::MinMax,
+        (false, true) => RateOffset::Max,
+        _ => RateOffset::None,
+    }
+}
+
+fn cvt_aspect_ratio(width: u16, height: u16) -> CvtAspectRatio {
+    let (w, h) = (width as u32, height as u32);
+    if w * 3 == h * 4 {
+        CvtAspectRatio::Ratio4_3
+    } else if w * 10 == h * 16 {
+        CvtAspectRatio::Ratio16_10
+    } else if w * 4 == h * 5 {
+        CvtAspectRatio::Ratio5_4
+    } else if w * 9 == h * 15 {
+        CvtAspectRatio::Ratio15_9
+    } else {
+        CvtAspectRatio::Ratio16_9
+    }
+}
+
+// The standard timing of a mode, if its size is one the standard timing can derive
+fn standard_timing_for((width, height, refresh_rate): Mode) -> Option<StandardTiming> {
+    if width % 8 != 0 || !(256..=2288).contains(&width) || !(60..=123).contains(&refresh_rate) {
+        return None;
+    }
+    let aspect_ratio = [
+        (AspectRatio::Ratio16_10, width * 10 / 16),
+        (AspectRatio::Ratio4_3, width * 3 / 4),
+        (AspectRatio::Ratio5_4, width * 4 / 5),
+        (AspectRatio::Ratio16_9, width * 9 / 16),
+    ]
+    .iter()
+    .find(|&&(_, h)| h == height)?
+    .0;
+
+    Some(StandardTiming {
+        horizontal_active_pixels: width,
+        vertical_active_lines: height,
+        aspect_ratio,
+        refresh_rate: refresh_rate as u8,
+    })
+}
+
+// Spreads the data blocks, then the detailed timings over as few CTA-861 extension blocks as
+// possible
+fn cta_extensions(data_blocks: Vec<DataBlock>, timings: Vec<DetailedTiming>, basic_audio: bool) -> Vec<CtaExtension> {
+    let new_extension = || CtaExtension {
+        revision: 3,
+        underscan: false,
+        basic_audio,
+        ycbcr444: false,
+        ycbcr422: false,
+        native_dtds: 0,
+        data_blocks: Vec::new(),
+        detailed_timings: Vec::new(),
+        explicit_dtd_offset: false,
+    };
+    let mut extensions: Vec<CtaExtension> = Vec::new();
+    let mut used = CTA_CAPACITY;
+
+    for block in data_blocks {
+        let size = cta::encode_data_block_collection(slice::from_ref(&block)).map_or(0, |data| data.len());
+        if used + size > CTA_CAPACITY {
+            extensions.push(new_extension());
+            used = 0;
+        }
+        used += size;
+        extensions.last_mut().unwrap().data_blocks.push(block);
+    }
+    for timing in timings {
+        if used + 18 > CTA_CAPACITY {
+            extensions.push(new_extension());
+            used = 0;
+        }
+        used += 18;
+        extensions.last_mut().unwrap().detailed_timings.push(timing);
+    }
+    extensions
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cta::{AudioFormat, AudioFormatDetail, BitDepths, SampleRates};
+
+	#[test]
+	fn test_edid_builder() {
+		let data = EdidBuilder::new()
+			.vendor(['A', 'B', 'C'])
+			.product(0x1234)
+			.serial(42)
+			.name("Virtual")
+			.size(600, 340)
+			.preferred_mode(2560, 1440, 60)
+			.mode(1920, 1080, 60)
+			.mode(1280, 1024, 75)
+			.mode(1366, 768, 60)
+			.mode(2560, 1440, 144)
+			.mode(3840, 2160, 30)
+			.build()
+			.unwrap();
+		assert_eq!(data.len(), 256);
+
+		let edid = parse(&data).unwrap();
+		assert_eq!(edid.header.vendor, ['A', 'B', 'C']);
+		assert_eq!((edid.header.product, edid.header.serial), (0x1234, 42));
+		assert_eq!(edid.display.size, ScreenSize::Physical { width: 60, height: 34 });
+
+		let preferred = edid.preferred_timing().unwrap();
+		assert_eq!((preferred.horizontal_active_pixels, preferred.vertical_active_lines), (2560, 1440));
+		assert_eq!((preferred.horizontal_size, preferred.vertical_size), (600, 340));
+		assert_eq!(edid.standard_timings.iter().flatten().count(), 2);
+		assert!(edid.descriptors.contains(&Descriptor::ProductName("Virtual".to_string())));
+
+		// The three other modes don't fit in the base block
+		let cta = match edid.extensions[0] {
+			Extension::Cta(ref cta) => cta,
+			ref e => panic!("unexpected extension {:?}", e),
+		};
+		assert_eq!(edid.descriptors.iter().filter(|d| matches!(d, Descriptor::DetailedTiming(_))).count(), 2);
+		assert_eq!(cta.detailed_timings.len(), 2);
+
+		let limits = edid.descriptors.iter().find_map(|d| match *d {
+			Descriptor::RangeLimits(ref limits) => Some(*limits),
+			_ => None,
+		}).unwrap();
+		assert_eq!((limits.min_vertical_rate, limits.max_vertical_rate), (29, 144));
+		assert!(limits.max_pixel_clock_khz() >= cta.detailed_timings.iter().map(|t| t.pixel_clock).max().unwrap());
+	}
+
+	#[test]
+	fn test_edid_builder_cta() {
+		let sad = ShortAudioDescriptor {
+			format: AudioFormat::Lpcm,
+			max_channels: 2,
+			sample_rates: SampleRates::all(),
+			detail: AudioFormatDetail::BitDepths(BitDepths::all()),
+		};
+		let vics: Vec<u8> = (1..=40).collect();
+		let edid = EdidBuilder::new().vics(&vics).audio(sad).build().unwrap();
+		let edid = parse(&edid).unwrap();
+
+		let cta = match edid.extensions[..] {
+			[Extension::Cta(ref cta)] => cta,
+			ref e => panic!("unexpected extensions {:?}", e),
+		};
+		assert!(cta.basic_audio);
+		assert_eq!(cta.data_blocks.len(), 3);
+		assert_eq!(cta.data_blocks[2], DataBlock::Audio(vec![sad]));
+	}
+
+	#[test]
+	fn test_edid_builder_unrepresentable() {
+		// 4K at 120 Hz needs a 1,097.75 MHz pixel clock
+		assert_eq!(cvt_timing(3840, 2160, 120.0, true).pixel_clock, 1_097_750);
+		let preferred = EdidBuilder::new().preferred_mode(3840, 2160, 120).build();
+		assert_eq!(preferred, Err(Error::BlockOverflow { block: 0 }));
+		let other = EdidBuilder::new().mode(3840, 2160, 120).build();
+		assert!(matches!(other, Err(Error::BlockOverflow { .. })));
+	}
+}
@@ -2,7 +2,7 @@ use std::char;
 
 // See https://en.wikipedia.org/wiki/Code_page_437
 
-const FORWARD_TABLE: &'static [u16] = &[
+const FORWARD_TABLE: &[u16] = &[
 	0x0000, 0x263A, 0x263B, 0x2665, 0x2666, 0x2663, 0x2660, 0x2022, 0x25D8, 0x25CB, 0x25D9, 0x2642, 0x2640, 0x266A, 0x266B, 0x263C,
 	0x25BA, 0x25C4, 0x2195, 0x203C, 0x00B6, 0x00A7, 0x25AC, 0x21A8, 0x2191, 0x2193, 0x2192, 0x2190, 0x221F, 0x2194, 0x25B2, 0x25BC,
 	0x0020, 0x0021, 0x0022, 0x0023, 0x0024, 0x0025, 0x0026, 0x0027, 0x0028, 0x0029, 0x002A, 0x002B, 0x002C, 0x002D, 0x002E, 0x002F,
@@ -24,3 +24,7 @@ const FORWARD_TABLE: &'static [u16] = &[
 pub fn forward(code: u8) -> char {
 	char::from_u32(FORWARD_TABLE[code as usize] as u32).unwrap()
 }
+
+pub fn reverse(c: char) -> Option<u8> {
+	FORWARD_TABLE.iter().position(|&v| v as u32 == c as u32).map(|code| code as u8)
+}
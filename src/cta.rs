@@ -0,0 +1,657 @@
+//! CTA-861 extension block.
+
+use nom::bytes::complete::take;
+use nom::combinator::{all_consuming, map};
+use nom::multi::many0;
+use nom::number::complete::{le_u24, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+
+use super::{encode_detailed_timing, parse_detailed_timing, DetailedTiming};
+
+mod audio;
+mod colorimetry;
+mod hdmi;
+mod hdr;
+mod vendor;
+mod vic;
+
+pub use self::audio::{
+    AacFlags, AudioFormat, AudioFormatDetail, BitDepths, SampleRates, ShortAudioDescriptor, SpeakerAllocation,
+};
+pub use self::colorimetry::{Colorimetries, Colorimetry, GamutMetadataProfiles, OverscanBehavior, VideoCapability};
+pub use self::hdmi::{
+    ContentTypes, DeepColor420, Dsc, DscMaxSlices, FrlRate, HdmiDeepColor, HdmiForumCapabilities, HdmiVideo,
+    HdmiVsdb, ImageSize, Latencies, Latency, Stereo3dStructure, Stereo3dStructures, Stereo3dVic, HDMI_FORUM_OUI,
+    HDMI_OUI,
+};
+pub use self::hdr::{Eotfs, HdrDynamicMetadata, HdrDynamicMetadataType, HdrStaticMetadata, StaticMetadataDescriptors};
+pub use self::vendor::{
+    DolbyVision, DolbyVision444, DolbyVisionInterface, DolbyVisionV0, DolbyVisionV1, DolbyVisionV2, FreeSync,
+    FreeSyncPremiumPro, Hdr10Plus, AMD_OUI, DOLBY_OUI, HDR10_PLUS_OUI,
+};
+pub use self::vic::{video_format, PictureAspectRatio, VideoFormat};
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ShortVideoDescriptor {
+	/// Video Identification Code, see `video_format`.
+	pub vic: u8,
+	/// The format is one of the native formats of the display.
+	pub native: bool,
+	/// The format is also supported in YCbCr 4:2:0, according to the 4:2:0 capability map.
+	pub ycbcr420: bool,
+}
+
+impl ShortVideoDescriptor {
+	pub fn format(&self) -> Option<VideoFormat> {
+		video_format(self.vic)
+	}
+}
+
+fn short_video_descriptor(b: u8) -> ShortVideoDescriptor {
+    // Bit 7 is the native flag for VICs 1 to 64, and part of the VIC above
+    let (vic, native) = match b & 0x7F {
+        1..=64 if b & 0x80 != 0 => (b & 0x7F, true),
+        _ => (b, false),
+    };
+    ShortVideoDescriptor { vic, native, ycbcr420: false }
+}
+
+fn parse_short_video_descriptors(input: &[u8], ycbcr420: bool) -> Vec<ShortVideoDescriptor> {
+    input
+        .iter()
+        .map(|&b| ShortVideoDescriptor { ycbcr420, ..short_video_descriptor(b) })
+        .collect()
+}
+
+fn encode_short_video_descriptor(svd: &ShortVideoDescriptor) -> u8 {
+    match svd.vic {
+        1..=64 if svd.native => svd.vic | 0x80,
+        vic => vic,
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum VendorSpecificBlock {
+	Hdmi(HdmiVsdb),
+	/// HDMI Forum Vendor-Specific Data Block.
+	HdmiForum(HdmiForumCapabilities),
+	FreeSync(FreeSync),
+	/// Block of another vendor, with its payload after the OUI.
+	Unknown { oui: u32, payload: Vec<u8> },
+}
+
+fn parse_vendor_specific_block(input: &[u8]) -> IResult<&[u8], VendorSpecificBlock> {
+    // The OUI is stored least significant byte first
+    let (input, oui) = le_u24(input)?;
+    match oui {
+        HDMI_OUI => map(hdmi::parse_hdmi_vsdb, VendorSpecificBlock::Hdmi)(input),
+        HDMI_FORUM_OUI => map(hdmi::parse_hdmi_forum_capabilities, VendorSpecificBlock::HdmiForum)(input),
+        AMD_OUI => map(vendor::parse_freesync, VendorSpecificBlock::FreeSync)(input),
+        _ => Ok((
+            &input[input.len()..],
+            VendorSpecificBlock::Unknown { oui, payload: input.to_vec() },
+        )),
+    }
+}
+
+fn encode_vendor_specific_block(block: &VendorSpecificBlock, out: &mut Vec<u8>) {
+    let oui = match *block {
+        VendorSpecificBlock::Hdmi(_) => HDMI_OUI,
+        VendorSpecificBlock::HdmiForum(_) => HDMI_FORUM_OUI,
+        VendorSpecificBlock::FreeSync(_) => AMD_OUI,
+        VendorSpecificBlock::Unknown { oui, .. } => oui,
+    };
+    out.extend_from_slice(&oui.to_le_bytes()[..3]);
+    match *block {
+        VendorSpecificBlock::Hdmi(ref vsdb) => hdmi::encode_hdmi_vsdb(vsdb, out),
+        VendorSpecificBlock::HdmiForum(ref caps) => hdmi::encode_hdmi_forum_capabilities(caps, out),
+        VendorSpecificBlock::FreeSync(ref freesync) => vendor::encode_freesync(freesync, out),
+        VendorSpecificBlock::Unknown { ref payload, .. } => out.extend_from_slice(payload),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum VendorSpecificVideoBlock {
+	DolbyVision(DolbyVision),
+	Hdr10Plus(Hdr10Plus),
+	/// Block of another vendor, with its payload after the OUI.
+	Unknown { oui: u32, payload: Vec<u8> },
+}
+
+fn parse_vendor_specific_video_block(input: &[u8]) -> IResult<&[u8], VendorSpecificVideoBlock> {
+    let (input, oui) = le_u24(input)?;
+    match oui {
+        DOLBY_OUI => map(vendor::parse_dolby_vision, VendorSpecificVideoBlock::DolbyVision)(input),
+        HDR10_PLUS_OUI => map(vendor::parse_hdr10_plus, VendorSpecificVideoBlock::Hdr10Plus)(input),
+        _ => Ok((
+            &input[input.len()..],
+            VendorSpecificVideoBlock::Unknown { oui, payload: input.to_vec() },
+        )),
+    }
+}
+
+fn encode_vendor_specific_video_block(block: &VendorSpecificVideoBlock, out: &mut Vec<u8>) {
+    let oui = match *block {
+        VendorSpecificVideoBlock::DolbyVision(_) => DOLBY_OUI,
+        VendorSpecificVideoBlock::Hdr10Plus(_) => HDR10_PLUS_OUI,
+        VendorSpecificVideoBlock::Unknown { oui, .. } => oui,
+    };
+    out.extend_from_slice(&oui.to_le_bytes()[..3]);
+    match *block {
+        VendorSpecificVideoBlock::DolbyVision(ref vision) => vendor::encode_dolby_vision(vision, out),
+        VendorSpecificVideoBlock::Hdr10Plus(ref hdr10_plus) => vendor::encode_hdr10_plus(hdr10_plus, out),
+        VendorSpecificVideoBlock::Unknown { ref payload, .. } => out.extend_from_slice(payload),
+    }
+}
+
+/// Data block of the data block collection, with its payload after the tag byte(s).
+#[derive(Debug, PartialEq, Clone)]
+pub enum DataBlock {
+	Audio(Vec<ShortAudioDescriptor>),
+	Video(Vec<ShortVideoDescriptor>),
+	VendorSpecific(VendorSpecificBlock),
+	SpeakerAllocation(SpeakerAllocation),
+	VesaDisplayTransferCharacteristic(Vec<u8>),
+	VideoCapability(VideoCapability),
+	VendorSpecificVideo(VendorSpecificVideoBlock),
+	VesaDisplayDevice(Vec<u8>),
+	Colorimetry(Colorimetry),
+	HdrStaticMetadata(HdrStaticMetadata),
+	HdrDynamicMetadata(Vec<HdrDynamicMetadata>),
+	VideoFormatPreference(Vec<u8>),
+	/// Video formats only supported in YCbCr 4:2:0.
+	Ycbcr420Video(Vec<ShortVideoDescriptor>),
+	/// Bitmap of the SVDs of the video data blocks which also support YCbCr 4:2:0, already
+	/// resolved into `ShortVideoDescriptor::ycbcr420`.
+	Ycbcr420CapabilityMap(Vec<u8>),
+	VendorSpecificAudio(Vec<u8>),
+	RoomConfiguration(Vec<u8>),
+	SpeakerLocation(Vec<u8>),
+	InfoFrame(Vec<u8>),
+	/// HDMI Forum Sink Capability Data Block.
+	HdmiForumScdb(HdmiForumCapabilities),
+	/// Data block with an unknown extended tag.
+	Extended(u8, Vec<u8>),
+	/// Data block with an unknown tag, or with a known one and a malformed payload.
+	Unknown(u8, Vec<u8>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CtaExtension {
+	pub revision: u8,
+	/// The display underscans IT video formats by default.
+	pub underscan: bool,
+	pub basic_audio: bool,
+	pub ycbcr444: bool,
+	pub ycbcr422: bool,
+	/// Number of native detailed timings, which come first.
+	pub native_dtds: u8,
+	/// Data blocks, only present starting with revision 3.
+	pub data_blocks: Vec<DataBlock>,
+	pub detailed_timings: Vec<DetailedTiming>,
+	/// The offset of the detailed timings is given even without data blocks nor detailed timings,
+	/// rather than being zero.
+	pub explicit_dtd_offset: bool,
+}
+
+impl CtaExtension {
+	/// Removes the audio data blocks and clears the basic audio support, so that sources don't
+	/// send audio to the display.
+	pub fn remove_audio(&mut self) {
+		self.basic_audio = false;
+		self.data_blocks.retain(|block| {
+			!matches!(
+				*block,
+				DataBlock::Audio(_)
+					| DataBlock::SpeakerAllocation(_)
+					| DataBlock::VendorSpecificAudio(_)
+					| DataBlock::RoomConfiguration(_)
+					| DataBlock::SpeakerLocation(_)
+			)
+		});
+	}
+
+	/// Removes the HDR static and dynamic metadata data blocks, as well as the Dolby Vision and
+	/// HDR10+ ones.
+	pub fn remove_hdr(&mut self) {
+		self.data_blocks.retain(|block| {
+			!matches!(
+				*block,
+				DataBlock::HdrStaticMetadata(_)
+					| DataBlock::HdrDynamicMetadata(_)
+					| DataBlock::VendorSpecificVideo(VendorSpecificVideoBlock::DolbyVision(_))
+					| DataBlock::VendorSpecificVideo(VendorSpecificVideoBlock::Hdr10Plus(_))
+			)
+		});
+	}
+}
+
+const EXTENDED_TAG: u8 = 7;
+
+fn parse_data_block(input: &[u8]) -> IResult<&[u8], DataBlock> {
+    let (input, header) = le_u8(input)?;
+    let (input, payload) = take(header & 0x1F)(input)?;
+    let tag = header >> 5;
+
+    // A malformed payload doesn't spoil the rest of the collection, the block is kept as is
+    match decode_data_block(tag, payload) {
+        Ok((_, block)) => Ok((input, block)),
+        Err(_) => Ok((input, DataBlock::Unknown(tag, payload.to_vec()))),
+    }
+}
+
+fn decode_data_block(tag: u8, payload: &[u8]) -> IResult<&[u8], DataBlock> {
+    if tag == EXTENDED_TAG {
+        let (payload, extended_tag) = le_u8(payload)?;
+        let data = payload.to_vec();
+        let block = match extended_tag {
+            0x00 => DataBlock::VideoCapability(colorimetry::parse_video_capability(payload)?.1),
+            0x01 => DataBlock::VendorSpecificVideo(parse_vendor_specific_video_block(payload)?.1),
+            0x02 => DataBlock::VesaDisplayDevice(data),
+            0x05 => DataBlock::Colorimetry(colorimetry::parse_colorimetry(payload)?.1),
+            0x06 => DataBlock::HdrStaticMetadata(hdr::parse_hdr_static_metadata(payload)?.1),
+            0x07 => DataBlock::HdrDynamicMetadata(hdr::parse_hdr_dynamic_metadata(payload)?.1),
+            0x0D => DataBlock::VideoFormatPreference(data),
+            0x0E => DataBlock::Ycbcr420Video(parse_short_video_descriptors(payload, true)),
+            0x0F => DataBlock::Ycbcr420CapabilityMap(data),
+            0x11 => DataBlock::VendorSpecificAudio(data),
+            0x13 => DataBlock::RoomConfiguration(data),
+            0x14 => DataBlock::SpeakerLocation(data),
+            0x20 => DataBlock::InfoFrame(data),
+            0x79 => DataBlock::HdmiForumScdb(hdmi::parse_hdmi_forum_scdb(payload)?.1),
+            _ => DataBlock::Extended(extended_tag, data),
+        };
+        return Ok((&payload[payload.len()..], block));
+    }
+
+    let data = payload.to_vec();
+    let block = match tag {
+        1 => DataBlock::Audio(audio::parse_audio_data_block(payload)?.1),
+        2 => DataBlock::Video(parse_short_video_descriptors(payload, false)),
+        3 => DataBlock::VendorSpecific(parse_vendor_specific_block(payload)?.1),
+        4 => DataBlock::SpeakerAllocation(audio::parse_speaker_allocation_data_block(payload)?.1),
+        5 => DataBlock::VesaDisplayTransferCharacteristic(data),
+        _ => DataBlock::Unknown(tag, data),
+    };
+    Ok((&payload[payload.len()..], block))
+}
+
+// Returns the tag and the payload of a data block, extended blocks starting with their extended tag
+fn encode_data_block_payload(block: &DataBlock) -> (u8, Vec<u8>) {
+    let mut payload = Vec::new();
+    let tag = match *block {
+        DataBlock::Audio(ref sads) => {
+            for sad in sads {
+                payload.extend_from_slice(&audio::encode_short_audio_descriptor(sad));
+            }
+            1
+        }
+        DataBlock::Video(ref svds) => {
+            payload.extend(svds.iter().map(encode_short_video_descriptor));
+            2
+        }
+        DataBlock::VendorSpecific(ref block) => {
+            encode_vendor_specific_block(block, &mut payload);
+            3
+        }
+        DataBlock::SpeakerAllocation(allocation) => {
+            payload.extend_from_slice(&audio::encode_speaker_allocation(allocation));
+            4
+        }
+        DataBlock::VesaDisplayTransferCharacteristic(ref data) => {
+            payload.extend_from_slice(data);
+            5
+        }
+        // Also extended blocks kept raw, whose data starts with the extended tag
+        DataBlock::Unknown(tag, ref data) => return (tag, data.clone()),
+        _ => EXTENDED_TAG,
+    };
+    if tag != EXTENDED_TAG {
+        return (tag, payload);
+    }
+
+    let (extended_tag, data) = match *block {
+        DataBlock::VideoCapability(ref capability) => {
+            payload.push(colorimetry::encode_video_capability(capability));
+            (0x00, None)
+        }
+        DataBlock::VendorSpecificVideo(ref block) => {
+            encode_vendor_specific_video_block(block, &mut payload);
+            (0x01, None)
+        }
+        DataBlock::VesaDisplayDevice(ref data) => (0x02, Some(data)),
+        DataBlock::Colorimetry(ref colorimetry) => {
+            payload.extend_from_slice(&colorimetry::encode_colorimetry(colorimetry));
+            (0x05, None)
+        }
+        DataBlock::HdrStaticMetadata(ref hdr) => {
+            hdr::encode_hdr_static_metadata(hdr, &mut payload);
+            (0x06, None)
+        }
+        DataBlock::HdrDynamicMetadata(ref entries) => {
+            hdr::encode_hdr_dynamic_metadata(entries, &mut payload);
+            (0x07, None)
+        }
+        DataBlock::VideoFormatPreference(ref data) => (0x0D, Some(data)),
+        DataBlock::Ycbcr420Video(ref svds) => {
+            payload.extend(svds.iter().map(encode_short_video_descriptor));
+            (0x0E, None)
+        }
+        DataBlock::Ycbcr420CapabilityMap(ref map) => (0x0F, Some(map)),
+        DataBlock::VendorSpecificAudio(ref data) => (0x11, Some(data)),
+        DataBlock::RoomConfiguration(ref data) => (0x13, Some(data)),
+        DataBlock::SpeakerLocation(ref data) => (0x14, Some(data)),
+        DataBlock::InfoFrame(ref data) => (0x20, Some(data)),
+        DataBlock::HdmiForumScdb(ref caps) => {
+            payload.extend_from_slice(&[0, 0]);
+            hdmi::encode_hdmi_forum_capabilities(caps, &mut payload);
+            (0x79, None)
+        }
+        DataBlock::Extended(extended_tag, ref data) => (extended_tag, Some(data)),
+        _ => unreachable!(),
+    };
+    if let Some(data) = data {
+        payload.extend_from_slice(data);
+    }
+    payload.insert(0, extended_tag);
+    (EXTENDED_TAG, payload)
+}
+
+/// Encodes a data block with its header, returns `None` if its payload is too long for it.
+fn encode_data_block(block: &DataBlock, out: &mut Vec<u8>) -> Option<()> {
+    let (tag, payload) = encode_data_block_payload(block);
+    if payload.len() > 0x1F {
+        return None;
+    }
+    out.push(tag << 5 | payload.len() as u8);
+    out.extend(payload);
+    Some(())
+}
+
+/// Encodes a data block collection, returns `None` if one of the blocks is too long.
+pub(crate) fn encode_data_block_collection(data_blocks: &[DataBlock]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    for block in data_blocks {
+        encode_data_block(block, &mut out)?;
+    }
+    Some(out)
+}
+
+// Marks the SVDs of the video data blocks, in order, which the YCbCr 4:2:0 capability map lists
+fn resolve_ycbcr420_capability_map(data_blocks: &mut [DataBlock]) {
+    let map = match data_blocks.iter().find_map(|block| match *block {
+        DataBlock::Ycbcr420CapabilityMap(ref map) => Some(map.clone()),
+        _ => None,
+    }) {
+        Some(map) => map,
+        None => return,
+    };
+
+    let svds = data_blocks
+        .iter_mut()
+        .filter_map(|block| match *block {
+            DataBlock::Video(ref mut svds) => Some(svds),
+            _ => None,
+        })
+        .flat_map(|svds| svds.iter_mut());
+    for (i, svd) in svds.enumerate() {
+        // An empty map means all the SVDs support YCbCr 4:2:0
+        svd.ycbcr420 = map.is_empty() || map.get(i / 8).is_some_and(|b| b & (1 << (i % 8)) != 0);
+    }
+}
+
+pub(crate) fn parse_data_block_collection(input: &[u8]) -> IResult<&[u8], Vec<DataBlock>> {
+    let (input, mut data_blocks) = all_consuming(many0(parse_data_block))(input)?;
+    resolve_ycbcr420_capability_map(&mut data_blocks);
+    Ok((input, data_blocks))
+}
+
+// Detailed timings follow each other until the padding, which starts with a zero pixel clock
+fn parse_detailed_timings(input: &[u8]) -> IResult<&[u8], Vec<DetailedTiming>> {
+    let mut input = input;
+    let mut timings = Vec::new();
+    while input.len() >= 18 && (input[0], input[1]) != (0, 0) {
+        let (rest, timing) = parse_detailed_timing(input)?;
+        timings.push(timing);
+        input = rest;
+    }
+    Ok((input, timings))
+}
+
+/// Parses a 128-byte CTA-861 extension block, whose checksum has already been verified.
+pub(crate) fn parse_cta_extension(block: &[u8]) -> IResult<&[u8], CtaExtension> {
+    let (_, (_tag, revision, dtd_offset, flags)) = tuple((le_u8, le_u8, le_u8, le_u8))(block)?;
+    let dtd_offset = dtd_offset as usize;
+    let checksum_offset = block.len() - 1;
+
+    // No flags in revision 1
+    let flags = if revision >= 2 { flags } else { 0 };
+
+    let mut cta = CtaExtension {
+        revision,
+        underscan: flags & 0x80 != 0,
+        basic_audio: flags & 0x40 != 0,
+        ycbcr444: flags & 0x20 != 0,
+        ycbcr422: flags & 0x10 != 0,
+        native_dtds: flags & 0x0F,
+        data_blocks: Vec::new(),
+        detailed_timings: Vec::new(),
+        explicit_dtd_offset: dtd_offset != 0,
+    };
+
+    // An offset of zero means there are neither detailed timings nor data blocks
+    if dtd_offset == 0 {
+        return Ok((&block[block.len()..], cta));
+    }
+    // An offset within the header or past the checksum leaves the contents undecoded, like a
+    // malformed data block, rather than spoiling the header flags and the rest of the EDID
+    if dtd_offset < 4 || dtd_offset > checksum_offset {
+        return Ok((&block[block.len()..], cta));
+    }
+
+    // Data blocks were introduced in revision 3
+    if revision >= 3 {
+        cta.data_blocks = parse_data_block_collection(&block[4..dtd_offset])?.1;
+    }
+    cta.detailed_timings = parse_detailed_timings(&block[dtd_offset..checksum_offset])?.1;
+
+    Ok((&block[block.len()..], cta))
+}
+
+/// Encodes a CTA-861 extension block, without its checksum. Returns `None` if its contents don't
+/// fit in a block, or if a detailed timing can't be encoded.
+pub(crate) fn encode_cta_extension(cta: &CtaExtension) -> Option<Vec<u8>> {
+    let data_blocks = if cta.revision >= 3 {
+        encode_data_block_collection(&cta.data_blocks)?
+    } else {
+        Vec::new()
+    };
+    let dtd_offset = if data_blocks.is_empty() && cta.detailed_timings.is_empty() && !cta.explicit_dtd_offset {
+        0
+    } else {
+        4 + data_blocks.len()
+    };
+    let flags = (cta.underscan as u8) << 7
+        | (cta.basic_audio as u8) << 6
+        | (cta.ycbcr444 as u8) << 5
+        | (cta.ycbcr422 as u8) << 4
+        | (cta.native_dtds & 0x0F);
+
+    let mut block = vec![0x02, cta.revision, dtd_offset as u8, if cta.revision >= 2 { flags } else { 0 }];
+    block.extend(data_blocks);
+    for timing in &cta.detailed_timings {
+        encode_detailed_timing(timing, &mut block)?;
+    }
+    if block.len() > 127 {
+        return None;
+    }
+    block.resize(128, 0);
+    Some(block)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::checksum;
+
+	fn cta_block(revision: u8, flags: u8, data_blocks: &[u8], dtds: &[u8]) -> Vec<u8> {
+		let mut block = vec![0x02, revision, 4 + data_blocks.len() as u8, flags];
+		block.extend_from_slice(data_blocks);
+		block.extend_from_slice(dtds);
+		block.resize(127, 0);
+		block.push(checksum(&block));
+		block
+	}
+
+	#[test]
+	fn test_cta_extension() {
+		let dtd = [0x02, 0x3A, 0x80, 0x18, 0x71, 0x38, 0x2D, 0x40, 0x58, 0x2C, 0x45, 0x00, 0x56, 0x50, 0x21, 0x00, 0x00, 0x1E];
+		let data_blocks = [
+			0x42, 0x90, 0x04, // Video
+			0x23, 0x09, 0x07, 0x07, // Audio
+			0xE2, 0x0F, 0x01, // YCbCr 4:2:0 capability map (extended tag)
+			0xE2, 0x42, 0x17, // Unknown extended tag
+			0x65, 0x03, 0x0C, 0x00, 0x10, 0x00, // HDMI
+			0x64, 0x56, 0x34, 0x12, 0x01, // Unknown vendor
+			0x67, 0xD8, 0x5D, 0xC4, 0x01, 0x78, 0x80, 0x00, // HDMI Forum
+			0xE5, 0x01, 0x8B, 0x84, 0x90, 0x01, // HDR10+
+		];
+		let block = cta_block(3, 0xF1, &data_blocks, &dtd);
+
+		let cta = parse_cta_extension(&block).unwrap().1;
+		assert_eq!(cta.revision, 3);
+		assert!(cta.underscan && cta.basic_audio && cta.ycbcr444 && cta.ycbcr422);
+		assert_eq!(cta.native_dtds, 1);
+		assert_eq!(cta.data_blocks, vec![
+			DataBlock::Video(vec![
+				ShortVideoDescriptor { vic: 16, native: true, ycbcr420: true },
+				ShortVideoDescriptor { vic: 4, native: false, ycbcr420: false },
+			]),
+			DataBlock::Audio(vec![ShortAudioDescriptor {
+				format: AudioFormat::Lpcm,
+				max_channels: 2,
+				sample_rates: SampleRates::RATE_32_KHZ | SampleRates::RATE_44_1_KHZ | SampleRates::RATE_48_KHZ,
+				detail: AudioFormatDetail::BitDepths(BitDepths::all()),
+			}]),
+			DataBlock::Ycbcr420CapabilityMap(vec![0x01]),
+			DataBlock::Extended(0x42, vec![0x17]),
+			DataBlock::VendorSpecific(VendorSpecificBlock::Hdmi(hdmi::parse_hdmi_vsdb(&[0x10, 0x00]).unwrap().1)),
+			DataBlock::VendorSpecific(VendorSpecificBlock::Unknown { oui: 0x123456, payload: vec![0x01] }),
+			DataBlock::VendorSpecific(VendorSpecificBlock::HdmiForum(
+				hdmi::parse_hdmi_forum_capabilities(&[0x01, 0x78, 0x80, 0x00]).unwrap().1,
+			)),
+			DataBlock::VendorSpecificVideo(VendorSpecificVideoBlock::Hdr10Plus(Hdr10Plus { application_version: 1 })),
+		]);
+		assert_eq!(cta.detailed_timings, vec![parse_detailed_timing(&dtd).unwrap().1]);
+	}
+
+	#[test]
+	fn test_encode_cta_extension() {
+		let dtd = [0x02, 0x3A, 0x80, 0x18, 0x71, 0x38, 0x2D, 0x40, 0x58, 0x2C, 0x45, 0x00, 0x56, 0x50, 0x21, 0x00, 0x00, 0x1E];
+		let data_blocks = [
+			0x42, 0x90, 0x04, // Video
+			0x23, 0x09, 0x07, 0x07, // Audio
+			0x83, 0x4F, 0x00, 0x00, // Speaker allocation
+			0xE2, 0x0F, 0x01, // YCbCr 4:2:0 capability map
+			0xE3, 0x05, 0xC3, 0x01, // Colorimetry
+			0xE2, 0x00, 0x4A, // Video capability
+			0xE6, 0x06, 0x0D, 0x01, 0x80, 0x60, 0x40, // HDR static metadata
+			0x6B, 0x03, 0x0C, 0x00, 0x10, 0x00, 0x38, 0x3C, 0x20, 0x00, 0x20, 0x01, // HDMI
+			0x67, 0xD8, 0x5D, 0xC4, 0x01, 0x78, 0x80, 0x00, // HDMI Forum
+			0xEB, 0x01, 0x46, 0xD0, 0x00, 0x2E, 0x5C, 0x9B, 0x69, 0x4A, 0x9F, 0xB1, // Dolby Vision
+		];
+		let block = cta_block(3, 0xF1, &data_blocks, &dtd);
+		let mut cta = parse_cta_extension(&block).unwrap().1;
+		assert_eq!(encode_cta_extension(&cta).unwrap()[..127], block[..127]);
+
+		// Without data blocks nor detailed timings, the offset is either 4 or zero
+		for offset in [4, 0] {
+			let mut empty = cta_block(3, 0x40, &[], &[]);
+			empty[2] = offset;
+			empty[127] = checksum(&empty[..127]);
+			let empty_cta = parse_cta_extension(&empty).unwrap().1;
+			assert_eq!(encode_cta_extension(&empty_cta).unwrap()[..127], empty[..127]);
+		}
+
+		// The payload of a data block is at most 31 bytes long
+		cta.data_blocks = vec![DataBlock::Unknown(6, vec![0; 32])];
+		assert_eq!(encode_cta_extension(&cta), None);
+	}
+
+	#[test]
+	fn test_short_video_descriptor() {
+		assert_eq!(short_video_descriptor(0x81), ShortVideoDescriptor { vic: 1, native: true, ycbcr420: false });
+		assert_eq!(short_video_descriptor(0x61), ShortVideoDescriptor { vic: 97, native: false, ycbcr420: false });
+		assert_eq!(short_video_descriptor(0xC1), ShortVideoDescriptor { vic: 193, native: false, ycbcr420: false });
+		assert_eq!(short_video_descriptor(0x61).format().unwrap().timing.horizontal_active_pixels, 3840);
+	}
+
+	#[test]
+	fn test_cta_extension_revision_1() {
+		let dtd = [0x01, 0x1D, 0x00, 0x72, 0x51, 0xD0, 0x1E, 0x20, 0x6E, 0x28, 0x55, 0x00, 0xC4, 0x8E, 0x21, 0x00, 0x00, 0x1E];
+		let block = cta_block(1, 0xF0, &[], &dtd);
+
+		let cta = parse_cta_extension(&block).unwrap().1;
+		assert!(!cta.underscan && !cta.basic_audio);
+		assert_eq!(cta.data_blocks, vec![]);
+		assert_eq!(cta.detailed_timings.len(), 1);
+	}
+
+	#[test]
+	fn test_cta_extension_malformed() {
+		// The data block claims more bytes than the collection holds
+		let mut block = cta_block(3, 0, &[0x43, 0x90, 0x04], &[]);
+		assert!(parse_cta_extension(&block).is_err());
+
+		// The offset points within the header, the flags are still decoded
+		block[2] = 2;
+		block[3] = 0x40;
+		let cta = parse_cta_extension(&block).unwrap().1;
+		assert!(cta.basic_audio);
+		assert_eq!((cta.data_blocks.len(), cta.detailed_timings.len()), (0, 0));
+
+		// Malformed payloads of known blocks are kept raw, and the following blocks parsed
+		let data_blocks = [
+			0x63, 0x03, 0x0C, 0x00, // HDMI VSDB without its physical address
+			0x24, 0x09, 0x07, 0x07, 0x00, // Audio with a partial SAD
+			0xE0, // Extended without its extended tag
+			0x41, 0x10, // Video
+		];
+		let cta = parse_cta_extension(&cta_block(3, 0, &data_blocks, &[])).unwrap().1;
+		assert_eq!(cta.data_blocks, vec![
+			DataBlock::Unknown(3, vec![0x03, 0x0C, 0x00]),
+			DataBlock::Unknown(1, vec![0x09, 0x07, 0x07, 0x00]),
+			DataBlock::Unknown(7, vec![]),
+			DataBlock::Video(vec![ShortVideoDescriptor { vic: 16, native: false, ycbcr420: false }]),
+		]);
+		assert_eq!(encode_data_block_collection(&cta.data_blocks).unwrap(), data_blocks);
+	}
+
+	#[test]
+	fn test_ycbcr420_capability_map() {
+		let data_blocks = [
+			0xE2, 0x0F, 0x02, // YCbCr 4:2:0 capability map
+			0x42, 0x90, 0x04, // Video
+			0x41, 0x61, // Video
+			0xE2, 0x0E, 0x60, // YCbCr 4:2:0 video
+		];
+		let cta = parse_cta_extension(&cta_block(3, 0, &data_blocks, &[])).unwrap().1;
+		assert_eq!(cta.data_blocks[1], DataBlock::Video(vec![
+			ShortVideoDescriptor { vic: 16, native: true, ycbcr420: false },
+			ShortVideoDescriptor { vic: 4, native: false, ycbcr420: true },
+		]));
+		assert_eq!(cta.data_blocks[2], DataBlock::Video(vec![
+			ShortVideoDescriptor { vic: 97, native: false, ycbcr420: false },
+		]));
+		assert_eq!(cta.data_blocks[3], DataBlock::Ycbcr420Video(vec![
+			ShortVideoDescriptor { vic: 96, native: false, ycbcr420: true },
+		]));
+
+		// An empty map applies to all the SVDs
+		let cta = parse_cta_extension(&cta_block(3, 0, &[0x41, 0x61, 0xE1, 0x0F], &[])).unwrap().1;
+		assert_eq!(cta.data_blocks[0], DataBlock::Video(vec![
+			ShortVideoDescriptor { vic: 97, native: false, ycbcr420: true },
+		]));
+	}
+}
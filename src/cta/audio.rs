@@ -0,0 +1,335 @@
+//! Audio Data Block and Speaker Allocation Data Block.
+
+use nom::combinator::{all_consuming, map};
+use nom::multi::many0;
+use nom::number::complete::le_u8;
+use nom::sequence::tuple;
+use nom::IResult;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AudioFormat {
+	Lpcm,
+	Ac3,
+	Mpeg1,
+	Mp3,
+	Mpeg2,
+	AacLc,
+	Dts,
+	Atrac,
+	OneBitAudio,
+	EnhancedAc3,
+	DtsHd,
+	Mat,
+	Dst,
+	WmaPro,
+	// Extended audio format codes
+	Mpeg4HeAac,
+	Mpeg4HeAacV2,
+	Mpeg4AacLc,
+	Dra,
+	Mpeg4HeAacMpegSurround,
+	Mpeg4AacLcMpegSurround,
+	MpegH3d,
+	Ac4,
+	Lpcm3d,
+	/// Reserved audio format code.
+	Reserved(u8),
+	/// Reserved extended audio format code.
+	ReservedExtended(u8),
+}
+
+bitflags! {
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct SampleRates: u8 {
+		const RATE_32_KHZ = 1 << 0;
+		const RATE_44_1_KHZ = 1 << 1;
+		const RATE_48_KHZ = 1 << 2;
+		const RATE_88_2_KHZ = 1 << 3;
+		const RATE_96_KHZ = 1 << 4;
+		const RATE_176_4_KHZ = 1 << 5;
+		const RATE_192_KHZ = 1 << 6;
+	}
+}
+
+bitflags! {
+	/// L-PCM sample sizes.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct BitDepths: u8 {
+		const DEPTH_16 = 1 << 0;
+		const DEPTH_20 = 1 << 1;
+		const DEPTH_24 = 1 << 2;
+	}
+}
+
+bitflags! {
+	/// MPEG-4 AAC capabilities.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct AacFlags: u8 {
+		/// Only explicitly signaled MPEG Surround is supported.
+		const MPEG_SURROUND_EXPLICIT = 1 << 0;
+		const FRAME_LENGTH_960 = 1 << 1;
+		const FRAME_LENGTH_1024 = 1 << 2;
+	}
+}
+
+/// Meaning of the last byte of a short audio descriptor, which depends on the format.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AudioFormatDetail {
+	/// L-PCM and L-PCM 3D.
+	BitDepths(BitDepths),
+	/// Maximum bit rate in kbit/s, for AC-3, MPEG-1, MP3, MPEG-2, AAC LC, DTS and ATRAC.
+	MaxBitrate(u16),
+	/// MPEG-4 AAC formats.
+	Aac(AacFlags),
+	/// MPEG-H 3D Audio.
+	MpegH3d { level: u8, profile: u8 },
+	/// Format-dependent value of the other formats, e.g. the WMA Pro profile.
+	Other(u8),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ShortAudioDescriptor {
+	pub format: AudioFormat,
+	/// Maximum number of channels, 0 for MPEG-H 3D Audio which stores its level instead.
+	pub max_channels: u8,
+	pub sample_rates: SampleRates,
+	pub detail: AudioFormatDetail,
+}
+
+fn short_audio_descriptor(b1: u8, b2: u8, b3: u8) -> ShortAudioDescriptor {
+    let code = (b1 >> 3) & 0xF;
+    let extended_code = b3 >> 3;
+    let format = match code {
+        1 => AudioFormat::Lpcm,
+        2 => AudioFormat::Ac3,
+        3 => AudioFormat::Mpeg1,
+        4 => AudioFormat::Mp3,
+        5 => AudioFormat::Mpeg2,
+        6 => AudioFormat::AacLc,
+        7 => AudioFormat::Dts,
+        8 => AudioFormat::Atrac,
+        9 => AudioFormat::OneBitAudio,
+        10 => AudioFormat::EnhancedAc3,
+        11 => AudioFormat::DtsHd,
+        12 => AudioFormat::Mat,
+        13 => AudioFormat::Dst,
+        14 => AudioFormat::WmaPro,
+        15 => match extended_code {
+            4 => AudioFormat::Mpeg4HeAac,
+            5 => AudioFormat::Mpeg4HeAacV2,
+            6 => AudioFormat::Mpeg4AacLc,
+            7 => AudioFormat::Dra,
+            8 => AudioFormat::Mpeg4HeAacMpegSurround,
+            10 => AudioFormat::Mpeg4AacLcMpegSurround,
+            11 => AudioFormat::MpegH3d,
+            12 => AudioFormat::Ac4,
+            13 => AudioFormat::Lpcm3d,
+            r => AudioFormat::ReservedExtended(r),
+        },
+        r => AudioFormat::Reserved(r),
+    };
+
+    let max_channels = match format {
+        AudioFormat::MpegH3d => 0,
+        // Up to 32 channels, the two high bits are stored in the unused bits 7 of the first
+        // two bytes
+        AudioFormat::Lpcm3d => ((b1 & 0x07) | ((b2 & 0x80) >> 4) | ((b1 & 0x80) >> 3)) + 1,
+        _ => (b1 & 0x07) + 1,
+    };
+
+    let detail = match format {
+        AudioFormat::Lpcm | AudioFormat::Lpcm3d => {
+            AudioFormatDetail::BitDepths(BitDepths::from_bits_truncate(b3))
+        }
+        AudioFormat::Ac3
+        | AudioFormat::Mpeg1
+        | AudioFormat::Mp3
+        | AudioFormat::Mpeg2
+        | AudioFormat::AacLc
+        | AudioFormat::Dts
+        | AudioFormat::Atrac => AudioFormatDetail::MaxBitrate(b3 as u16 * 8),
+        AudioFormat::Mpeg4HeAac
+        | AudioFormat::Mpeg4HeAacV2
+        | AudioFormat::Mpeg4AacLc
+        | AudioFormat::Mpeg4HeAacMpegSurround
+        | AudioFormat::Mpeg4AacLcMpegSurround => {
+            AudioFormatDetail::Aac(AacFlags::from_bits_truncate(b3))
+        }
+        AudioFormat::MpegH3d => AudioFormatDetail::MpegH3d {
+            level: b1 & 0x07,
+            profile: b3 & 0x07,
+        },
+        // The extended formats store their code in the high bits
+        _ if code == 15 => AudioFormatDetail::Other(b3 & 0x07),
+        _ => AudioFormatDetail::Other(b3),
+    };
+
+    ShortAudioDescriptor {
+        format,
+        max_channels,
+        sample_rates: SampleRates::from_bits_truncate(b2),
+        detail,
+    }
+}
+
+pub(crate) fn encode_short_audio_descriptor(sad: &ShortAudioDescriptor) -> [u8; 3] {
+    let (code, extended_code) = match sad.format {
+        AudioFormat::Lpcm => (1, 0),
+        AudioFormat::Ac3 => (2, 0),
+        AudioFormat::Mpeg1 => (3, 0),
+        AudioFormat::Mp3 => (4, 0),
+        AudioFormat::Mpeg2 => (5, 0),
+        AudioFormat::AacLc => (6, 0),
+        AudioFormat::Dts => (7, 0),
+        AudioFormat::Atrac => (8, 0),
+        AudioFormat::OneBitAudio => (9, 0),
+        AudioFormat::EnhancedAc3 => (10, 0),
+        AudioFormat::DtsHd => (11, 0),
+        AudioFormat::Mat => (12, 0),
+        AudioFormat::Dst => (13, 0),
+        AudioFormat::WmaPro => (14, 0),
+        AudioFormat::Mpeg4HeAac => (15, 4),
+        AudioFormat::Mpeg4HeAacV2 => (15, 5),
+        AudioFormat::Mpeg4AacLc => (15, 6),
+        AudioFormat::Dra => (15, 7),
+        AudioFormat::Mpeg4HeAacMpegSurround => (15, 8),
+        AudioFormat::Mpeg4AacLcMpegSurround => (15, 10),
+        AudioFormat::MpegH3d => (15, 11),
+        AudioFormat::Ac4 => (15, 12),
+        AudioFormat::Lpcm3d => (15, 13),
+        AudioFormat::Reserved(r) => (r & 0xF, 0),
+        AudioFormat::ReservedExtended(r) => (15, r & 0x1F),
+    };
+
+    let channels = sad.max_channels.saturating_sub(1);
+    let (mut b1, mut b2) = (code << 3 | (channels & 0x07), sad.sample_rates.bits());
+    match (sad.format, sad.detail) {
+        (AudioFormat::MpegH3d, AudioFormatDetail::MpegH3d { level, .. }) => b1 = code << 3 | (level & 0x07),
+        (AudioFormat::Lpcm3d, _) => {
+            b1 |= (channels & 0x10) << 3;
+            b2 |= (channels & 0x08) << 4;
+        }
+        _ => {}
+    }
+
+    let detail = match sad.detail {
+        AudioFormatDetail::BitDepths(depths) => depths.bits(),
+        AudioFormatDetail::MaxBitrate(rate) => (rate / 8) as u8,
+        AudioFormatDetail::Aac(flags) => flags.bits(),
+        AudioFormatDetail::MpegH3d { profile, .. } => profile,
+        AudioFormatDetail::Other(v) => v,
+    };
+    // The extended formats store their code in the high bits
+    let b3 = if code == 15 { extended_code << 3 | (detail & 0x07) } else { detail };
+    [b1, b2, b3]
+}
+
+pub(crate) fn parse_audio_data_block(input: &[u8]) -> IResult<&[u8], Vec<ShortAudioDescriptor>> {
+    all_consuming(many0(map(tuple((le_u8, le_u8, le_u8)), |(b1, b2, b3)| {
+        short_audio_descriptor(b1, b2, b3)
+    })))(input)
+}
+
+bitflags! {
+	/// Speakers present, the first byte of the block is stored in the lower bits.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct SpeakerAllocation: u32 {
+		const FRONT_LEFT_RIGHT = 1 << 0;
+		const LOW_FREQUENCY_EFFECTS = 1 << 1;
+		const FRONT_CENTER = 1 << 2;
+		const BACK_LEFT_RIGHT = 1 << 3;
+		const BACK_CENTER = 1 << 4;
+		const FRONT_LEFT_RIGHT_CENTER = 1 << 5;
+		const REAR_LEFT_RIGHT_CENTER = 1 << 6;
+		const FRONT_LEFT_RIGHT_WIDE = 1 << 7;
+		const TOP_FRONT_LEFT_RIGHT = 1 << 8;
+		const TOP_CENTER = 1 << 9;
+		const TOP_FRONT_CENTER = 1 << 10;
+		const LEFT_RIGHT_SURROUND = 1 << 11;
+		const LOW_FREQUENCY_EFFECTS_2 = 1 << 12;
+		const TOP_BACK_CENTER = 1 << 13;
+		const SIDE_LEFT_RIGHT = 1 << 14;
+		const TOP_SIDE_LEFT_RIGHT = 1 << 15;
+		const TOP_BACK_LEFT_RIGHT = 1 << 16;
+		const BOTTOM_FRONT_CENTER = 1 << 17;
+		const BOTTOM_FRONT_LEFT_RIGHT = 1 << 18;
+		const TOP_LEFT_RIGHT_SURROUND = 1 << 19;
+	}
+}
+
+pub(crate) fn parse_speaker_allocation_data_block(input: &[u8]) -> IResult<&[u8], SpeakerAllocation> {
+    map(tuple((le_u8, le_u8, le_u8)), |(b1, b2, b3)| {
+        SpeakerAllocation::from_bits_truncate(b1 as u32 | (b2 as u32) << 8 | (b3 as u32) << 16)
+    })(input)
+}
+
+pub(crate) fn encode_speaker_allocation(allocation: SpeakerAllocation) -> [u8; 3] {
+    let bytes = allocation.bits().to_le_bytes();
+    [bytes[0], bytes[1], bytes[2]]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_audio_data_block() {
+		let d = [
+			0x09, 0x7F, 0x07, // L-PCM, 2 channels
+			0x15, 0x07, 0x50, // AC-3, 6 channels, 640 kbit/s
+			0x67, 0x04, 0x03, // MAT, 8 channels
+			0x7F, 0x86, 0x6D, // L-PCM 3D, 16 channels
+			0x7A, 0x06, 0x5B, // MPEG-H 3D Audio level 2
+		];
+
+		let sads = parse_audio_data_block(&d).unwrap().1;
+		assert_eq!(sads, vec![
+			ShortAudioDescriptor {
+				format: AudioFormat::Lpcm,
+				max_channels: 2,
+				sample_rates: SampleRates::all(),
+				detail: AudioFormatDetail::BitDepths(BitDepths::all()),
+			},
+			ShortAudioDescriptor {
+				format: AudioFormat::Ac3,
+				max_channels: 6,
+				sample_rates: SampleRates::RATE_32_KHZ | SampleRates::RATE_44_1_KHZ | SampleRates::RATE_48_KHZ,
+				detail: AudioFormatDetail::MaxBitrate(640),
+			},
+			ShortAudioDescriptor {
+				format: AudioFormat::Mat,
+				max_channels: 8,
+				sample_rates: SampleRates::RATE_48_KHZ,
+				detail: AudioFormatDetail::Other(0x03),
+			},
+			ShortAudioDescriptor {
+				format: AudioFormat::Lpcm3d,
+				max_channels: 16,
+				sample_rates: SampleRates::RATE_44_1_KHZ | SampleRates::RATE_48_KHZ,
+				detail: AudioFormatDetail::BitDepths(BitDepths::DEPTH_16 | BitDepths::DEPTH_24),
+			},
+			ShortAudioDescriptor {
+				format: AudioFormat::MpegH3d,
+				max_channels: 0,
+				sample_rates: SampleRates::RATE_44_1_KHZ | SampleRates::RATE_48_KHZ,
+				detail: AudioFormatDetail::MpegH3d { level: 2, profile: 3 },
+			},
+		]);
+
+		assert!(parse_audio_data_block(&d[..4]).is_err());
+	}
+
+	#[test]
+	fn test_speaker_allocation_data_block() {
+		let allocation = parse_speaker_allocation_data_block(&[0x4F, 0x01, 0x00]).unwrap().1;
+		assert_eq!(
+			allocation,
+			SpeakerAllocation::FRONT_LEFT_RIGHT
+				| SpeakerAllocation::LOW_FREQUENCY_EFFECTS
+				| SpeakerAllocation::FRONT_CENTER
+				| SpeakerAllocation::BACK_LEFT_RIGHT
+				| SpeakerAllocation::REAR_LEFT_RIGHT_CENTER
+				| SpeakerAllocation::TOP_FRONT_LEFT_RIGHT
+		);
+	}
+}
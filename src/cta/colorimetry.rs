@@ -0,0 +1,141 @@
+//! Colorimetry Data Block and Video Capability Data Block.
+
+use nom::combinator::map;
+use nom::number::complete::le_u8;
+use nom::sequence::tuple;
+use nom::IResult;
+
+bitflags! {
+	/// Colorimetry standards, the second byte of the block is stored in the upper bits.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct Colorimetries: u16 {
+		const XVYCC_601 = 1 << 0;
+		const XVYCC_709 = 1 << 1;
+		const SYCC_601 = 1 << 2;
+		const OPYCC_601 = 1 << 3;
+		const OPRGB = 1 << 4;
+		const BT2020_CYCC = 1 << 5;
+		const BT2020_YCC = 1 << 6;
+		const BT2020_RGB = 1 << 7;
+		const ICTCP = 1 << 14;
+		/// DCI-P3 with the SMPTE RP 431-2 white point.
+		const DCI_P3 = 1 << 15;
+	}
+}
+
+bitflags! {
+	/// Gamut metadata profiles MD0 to MD3.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct GamutMetadataProfiles: u8 {
+		const MD0 = 1 << 0;
+		const MD1 = 1 << 1;
+		const MD2 = 1 << 2;
+		const MD3 = 1 << 3;
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Colorimetry {
+	pub colorimetries: Colorimetries,
+	pub metadata_profiles: GamutMetadataProfiles,
+}
+
+pub(crate) fn parse_colorimetry(input: &[u8]) -> IResult<&[u8], Colorimetry> {
+    map(tuple((le_u8, le_u8)), |(b1, b2)| Colorimetry {
+        colorimetries: Colorimetries::from_bits_truncate(b1 as u16 | (b2 as u16) << 8),
+        metadata_profiles: GamutMetadataProfiles::from_bits_truncate(b2),
+    })(input)
+}
+
+pub(crate) fn encode_colorimetry(colorimetry: &Colorimetry) -> [u8; 2] {
+    let bits = colorimetry.colorimetries.bits();
+    [bits as u8, (bits >> 8) as u8 | colorimetry.metadata_profiles.bits()]
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum OverscanBehavior {
+	/// Not supported for IT and CE video formats, no data for PT video formats.
+	Unspecified,
+	AlwaysOverscanned,
+	AlwaysUnderscanned,
+	/// Both overscan and underscan are supported.
+	Both,
+}
+
+fn overscan_behavior(v: u8) -> OverscanBehavior {
+    match v & 0x3 {
+        0 => OverscanBehavior::Unspecified,
+        1 => OverscanBehavior::AlwaysOverscanned,
+        2 => OverscanBehavior::AlwaysUnderscanned,
+        _ => OverscanBehavior::Both,
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct VideoCapability {
+	/// The YCC quantization range can be selected in the AVI InfoFrame.
+	pub quantization_selectable_ycc: bool,
+	/// The RGB quantization range can be selected in the AVI InfoFrame.
+	pub quantization_selectable_rgb: bool,
+	/// Preferred video formats, used for the other formats when the IT and CE behaviors are unspecified.
+	pub pt_overscan: OverscanBehavior,
+	/// IT video formats.
+	pub it_overscan: OverscanBehavior,
+	/// CE video formats.
+	pub ce_overscan: OverscanBehavior,
+}
+
+pub(crate) fn parse_video_capability(input: &[u8]) -> IResult<&[u8], VideoCapability> {
+    map(le_u8, |b| VideoCapability {
+        quantization_selectable_ycc: b & 0x80 != 0,
+        quantization_selectable_rgb: b & 0x40 != 0,
+        pt_overscan: overscan_behavior(b >> 4),
+        it_overscan: overscan_behavior(b >> 2),
+        ce_overscan: overscan_behavior(b),
+    })(input)
+}
+
+pub(crate) fn encode_video_capability(capability: &VideoCapability) -> u8 {
+    let overscan = |behavior: OverscanBehavior| match behavior {
+        OverscanBehavior::Unspecified => 0,
+        OverscanBehavior::AlwaysOverscanned => 1,
+        OverscanBehavior::AlwaysUnderscanned => 2,
+        OverscanBehavior::Both => 3,
+    };
+    (capability.quantization_selectable_ycc as u8) << 7
+        | (capability.quantization_selectable_rgb as u8) << 6
+        | overscan(capability.pt_overscan) << 4
+        | overscan(capability.it_overscan) << 2
+        | overscan(capability.ce_overscan)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_colorimetry() {
+		let colorimetry = parse_colorimetry(&[0xE3, 0x81]).unwrap().1;
+		assert_eq!(colorimetry, Colorimetry {
+			colorimetries: Colorimetries::XVYCC_601
+				| Colorimetries::XVYCC_709
+				| Colorimetries::BT2020_CYCC
+				| Colorimetries::BT2020_YCC
+				| Colorimetries::BT2020_RGB
+				| Colorimetries::DCI_P3,
+			metadata_profiles: GamutMetadataProfiles::MD0,
+		});
+	}
+
+	#[test]
+	fn test_video_capability() {
+		let capability = parse_video_capability(&[0x4E]).unwrap().1;
+		assert_eq!(capability, VideoCapability {
+			quantization_selectable_ycc: false,
+			quantization_selectable_rgb: true,
+			pt_overscan: OverscanBehavior::Unspecified,
+			it_overscan: OverscanBehavior::Both,
+			ce_overscan: OverscanBehavior::AlwaysUnderscanned,
+		});
+	}
+}
@@ -0,0 +1,678 @@
+//! HDMI vendor-specific data blocks.
+
+use nom::bytes::complete::take;
+use nom::combinator::{all_consuming, cond, map};
+use nom::multi::many0;
+use nom::number::complete::{be_u16, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+
+/// IEEE OUI of HDMI Licensing, LLC.
+pub const HDMI_OUI: u32 = 0x000C03;
+/// IEEE OUI of the HDMI Forum.
+pub const HDMI_FORUM_OUI: u32 = 0xC45DD8;
+
+bitflags! {
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct HdmiDeepColor: u8 {
+		/// Deep color modes are also supported in YCbCr 4:4:4.
+		const DC_Y444 = 1 << 3;
+		const DC_30BIT = 1 << 4;
+		const DC_36BIT = 1 << 5;
+		const DC_48BIT = 1 << 6;
+	}
+}
+
+bitflags! {
+	/// Content types supported by the ITC bit of the AVI InfoFrame.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct ContentTypes: u8 {
+		const GRAPHICS = 1 << 0;
+		const PHOTO = 1 << 1;
+		const CINEMA = 1 << 2;
+		const GAME = 1 << 3;
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Latency {
+	/// No information is provided.
+	Unknown,
+	/// The video or audio isn't supported on this path.
+	Unsupported,
+	Milliseconds(u16),
+}
+
+fn latency(v: u8) -> Latency {
+    match v {
+        0 => Latency::Unknown,
+        255 => Latency::Unsupported,
+        v => Latency::Milliseconds((v as u16 - 1) * 2),
+    }
+}
+
+fn encode_latency(latency: Latency) -> u8 {
+    match latency {
+        Latency::Unknown => 0,
+        Latency::Unsupported => 255,
+        Latency::Milliseconds(ms) => (ms / 2 + 1).min(254) as u8,
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Latencies {
+	pub video: Latency,
+	pub audio: Latency,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ImageSize {
+	/// No additional information.
+	NoInfo,
+	/// The aspect ratio is correct but the size may not be.
+	AspectRatioOnly,
+	/// The size is correct, rounded to the nearest cm.
+	Centimeters,
+	/// The size is correct, divided by 5 and rounded to the nearest 5 cm.
+	FiveCentimeters,
+}
+
+bitflags! {
+	/// 3D structures supported by a set of video formats.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct Stereo3dStructures: u16 {
+		const FRAME_PACKING = 1 << 0;
+		const FIELD_ALTERNATIVE = 1 << 1;
+		const LINE_ALTERNATIVE = 1 << 2;
+		const SIDE_BY_SIDE_FULL = 1 << 3;
+		const L_DEPTH = 1 << 4;
+		const L_DEPTH_GRAPHICS = 1 << 5;
+		const TOP_AND_BOTTOM = 1 << 6;
+		const SIDE_BY_SIDE_HALF = 1 << 8;
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Stereo3dStructure {
+	FramePacking,
+	FieldAlternative,
+	LineAlternative,
+	SideBySideFull,
+	LDepth,
+	LDepthGraphics,
+	TopAndBottom,
+	/// Side-by-side (half), with its subsampling method.
+	SideBySideHalf { detail: u8 },
+	/// Reserved structure, with its detail if it has one.
+	Reserved(u8, Option<u8>),
+}
+
+/// 3D structure supported by one of the SVDs.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Stereo3dVic {
+	/// Index of the SVD in the video data blocks, the 2D_VIC_order field.
+	pub vic_order: u8,
+	pub structure: Stereo3dStructure,
+}
+
+/// HDMI video information, present when the HDMI_Video_present bit is set.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HdmiVideo {
+	/// The mandatory 3D formats are supported.
+	pub stereo_3d: bool,
+	pub image_size: ImageSize,
+	/// HDMI VICs, for the 4K formats not listed in the first CTA-861 revisions.
+	pub vics: Vec<u8>,
+	/// 3D structures supported by the first 16 SVDs (3D_Structure_ALL).
+	pub structure_all: Option<Stereo3dStructures>,
+	/// Which of the first 16 SVDs support `structure_all` (3D_MASK), all of them if `None`.
+	pub structure_mask: Option<u16>,
+	pub vics_3d: Vec<Stereo3dVic>,
+}
+
+/// HDMI 1.4 Vendor-Specific Data Block.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HdmiVsdb {
+	/// CEC physical address a.b.c.d.
+	pub physical_address: [u8; 4],
+	pub supports_ai: bool,
+	pub deep_color: HdmiDeepColor,
+	pub dvi_dual: bool,
+	/// Maximum TMDS clock in MHz.
+	pub max_tmds_clock: Option<u16>,
+	pub content_types: ContentTypes,
+	pub latency: Option<Latencies>,
+	pub interlaced_latency: Option<Latencies>,
+	pub video: Option<HdmiVideo>,
+}
+
+fn parse_latencies(input: &[u8]) -> IResult<&[u8], Latencies> {
+    map(tuple((le_u8, le_u8)), |(video, audio)| Latencies {
+        video: latency(video),
+        audio: latency(audio),
+    })(input)
+}
+
+fn parse_stereo_3d_vic(input: &[u8]) -> IResult<&[u8], Stereo3dVic> {
+    let (input, b) = le_u8(input)?;
+    let code = b & 0xF;
+    // Structures starting with side-by-side (half) have an extra detail byte
+    let (input, detail) = cond(code >= 8, map(le_u8, |d| d >> 4))(input)?;
+    let structure = match (code, detail) {
+        (0, _) => Stereo3dStructure::FramePacking,
+        (1, _) => Stereo3dStructure::FieldAlternative,
+        (2, _) => Stereo3dStructure::LineAlternative,
+        (3, _) => Stereo3dStructure::SideBySideFull,
+        (4, _) => Stereo3dStructure::LDepth,
+        (5, _) => Stereo3dStructure::LDepthGraphics,
+        (6, _) => Stereo3dStructure::TopAndBottom,
+        (8, Some(detail)) => Stereo3dStructure::SideBySideHalf { detail },
+        (code, detail) => Stereo3dStructure::Reserved(code, detail),
+    };
+    Ok((input, Stereo3dVic { vic_order: b >> 4, structure }))
+}
+
+fn parse_hdmi_video(input: &[u8]) -> IResult<&[u8], HdmiVideo> {
+    let (input, (flags, lengths)) = tuple((le_u8, le_u8))(input)?;
+    let (input, vics) = take(lengths >> 5)(input)?;
+    let (input, data_3d) = take(lengths & 0x1F)(input)?;
+
+    let multi_present = (flags >> 5) & 0x3;
+    let (data_3d, structure_all) = cond(
+        multi_present == 1 || multi_present == 2,
+        map(be_u16, Stereo3dStructures::from_bits_truncate),
+    )(data_3d)?;
+    let (data_3d, structure_mask) = cond(multi_present == 2, be_u16)(data_3d)?;
+    let (_, vics_3d) = all_consuming(many0(parse_stereo_3d_vic))(data_3d)?;
+
+    Ok((
+        input,
+        HdmiVideo {
+            stereo_3d: flags & 0x80 != 0,
+            image_size: match (flags >> 3) & 0x3 {
+                0 => ImageSize::NoInfo,
+                1 => ImageSize::AspectRatioOnly,
+                2 => ImageSize::Centimeters,
+                _ => ImageSize::FiveCentimeters,
+            },
+            vics: vics.to_vec(),
+            structure_all,
+            structure_mask,
+            vics_3d,
+        },
+    ))
+}
+
+fn encode_stereo_3d_vic(vic: &Stereo3dVic, out: &mut Vec<u8>) {
+    let (code, detail) = match vic.structure {
+        Stereo3dStructure::FramePacking => (0, None),
+        Stereo3dStructure::FieldAlternative => (1, None),
+        Stereo3dStructure::LineAlternative => (2, None),
+        Stereo3dStructure::SideBySideFull => (3, None),
+        Stereo3dStructure::LDepth => (4, None),
+        Stereo3dStructure::LDepthGraphics => (5, None),
+        Stereo3dStructure::TopAndBottom => (6, None),
+        Stereo3dStructure::SideBySideHalf { detail } => (8, Some(detail)),
+        Stereo3dStructure::Reserved(code, detail) => (code & 0xF, detail),
+    };
+    out.push(vic.vic_order << 4 | code);
+    if code >= 8 {
+        out.push(detail.unwrap_or(0) << 4);
+    }
+}
+
+fn encode_hdmi_video(video: &HdmiVideo, out: &mut Vec<u8>) {
+    let mut data_3d = Vec::new();
+    if let Some(structure_all) = video.structure_all {
+        data_3d.extend_from_slice(&structure_all.bits().to_be_bytes());
+    }
+    let multi_present = match (video.structure_all, video.structure_mask) {
+        (Some(_), Some(mask)) => {
+            data_3d.extend_from_slice(&mask.to_be_bytes());
+            2
+        }
+        (Some(_), None) => 1,
+        _ => 0,
+    };
+    for vic in &video.vics_3d {
+        encode_stereo_3d_vic(vic, &mut data_3d);
+    }
+
+    let image_size = match video.image_size {
+        ImageSize::NoInfo => 0,
+        ImageSize::AspectRatioOnly => 1,
+        ImageSize::Centimeters => 2,
+        ImageSize::FiveCentimeters => 3,
+    };
+    out.push((video.stereo_3d as u8) << 7 | multi_present << 5 | image_size << 3);
+    out.push((video.vics.len() as u8) << 5 | (data_3d.len() as u8 & 0x1F));
+    out.extend_from_slice(&video.vics);
+    out.extend(data_3d);
+}
+
+/// Parses the payload of an HDMI VSDB, after the OUI.
+pub(crate) fn parse_hdmi_vsdb(input: &[u8]) -> IResult<&[u8], HdmiVsdb> {
+    let (input, (ab, cd)) = tuple((le_u8, le_u8))(input)?;
+    let mut vsdb = HdmiVsdb {
+        physical_address: [ab >> 4, ab & 0xF, cd >> 4, cd & 0xF],
+        supports_ai: false,
+        deep_color: HdmiDeepColor::empty(),
+        dvi_dual: false,
+        max_tmds_clock: None,
+        content_types: ContentTypes::empty(),
+        latency: None,
+        interlaced_latency: None,
+        video: None,
+    };
+
+    // All the following fields are optional, the block can end after any of them
+    if input.is_empty() {
+        return Ok((input, vsdb));
+    }
+    let (input, flags) = le_u8(input)?;
+    vsdb.supports_ai = flags & 0x80 != 0;
+    vsdb.deep_color = HdmiDeepColor::from_bits_truncate(flags);
+    vsdb.dvi_dual = flags & 0x01 != 0;
+
+    if input.is_empty() {
+        return Ok((input, vsdb));
+    }
+    let (input, max_tmds_clock) = le_u8(input)?;
+    if max_tmds_clock != 0 {
+        vsdb.max_tmds_clock = Some(max_tmds_clock as u16 * 5);
+    }
+
+    if input.is_empty() {
+        return Ok((input, vsdb));
+    }
+    let (input, flags) = le_u8(input)?;
+    vsdb.content_types = ContentTypes::from_bits_truncate(flags);
+    let (input, latency) = cond(flags & 0x80 != 0, parse_latencies)(input)?;
+    let (input, interlaced_latency) = cond(flags & 0x40 != 0, parse_latencies)(input)?;
+    let (input, video) = cond(flags & 0x20 != 0, parse_hdmi_video)(input)?;
+    vsdb.latency = latency;
+    vsdb.interlaced_latency = interlaced_latency;
+    vsdb.video = video;
+
+    Ok((input, vsdb))
+}
+
+/// Encodes the payload of an HDMI VSDB, after the OUI. The optional fields are written up to the
+/// last one which isn't empty.
+pub(crate) fn encode_hdmi_vsdb(vsdb: &HdmiVsdb, out: &mut Vec<u8>) {
+    let address = vsdb.physical_address;
+    out.extend_from_slice(&[address[0] << 4 | (address[1] & 0xF), address[2] << 4 | (address[3] & 0xF)]);
+
+    let flags = (vsdb.supports_ai as u8) << 7 | vsdb.deep_color.bits() | vsdb.dvi_dual as u8;
+    let max_tmds_clock = vsdb.max_tmds_clock.map_or(0, |clock| (clock / 5) as u8);
+    let content_flags = vsdb.content_types.bits()
+        | (vsdb.latency.is_some() as u8) << 7
+        | (vsdb.interlaced_latency.is_some() as u8) << 6
+        | (vsdb.video.is_some() as u8) << 5;
+
+    if content_flags != 0 {
+        out.extend_from_slice(&[flags, max_tmds_clock, content_flags]);
+    } else if max_tmds_clock != 0 {
+        out.extend_from_slice(&[flags, max_tmds_clock]);
+    } else if flags != 0 {
+        out.push(flags);
+    }
+    for latencies in vsdb.latency.iter().chain(vsdb.interlaced_latency.iter()) {
+        out.extend_from_slice(&[encode_latency(latencies.video), encode_latency(latencies.audio)]);
+    }
+    if let Some(ref video) = vsdb.video {
+        encode_hdmi_video(video, out);
+    }
+}
+
+bitflags! {
+	/// Deep color modes supported in YCbCr 4:2:0.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct DeepColor420: u8 {
+		const DC_30BIT = 1 << 0;
+		const DC_36BIT = 1 << 1;
+		const DC_48BIT = 1 << 2;
+	}
+}
+
+/// Fixed Rate Link configuration.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum FrlRate {
+	Unsupported,
+	/// Number of lanes and rate per lane in Gbit/s.
+	Supported { lanes: u8, rate: u8 },
+	Reserved(u8),
+}
+
+impl FrlRate {
+	/// Total link rate in Gbit/s.
+	pub fn bandwidth(&self) -> Option<u8> {
+		match *self {
+			FrlRate::Supported { lanes, rate } => Some(lanes * rate),
+			_ => None,
+		}
+	}
+}
+
+fn frl_rate(v: u8) -> FrlRate {
+    match v {
+        0 => FrlRate::Unsupported,
+        1 => FrlRate::Supported { lanes: 3, rate: 3 },
+        2 => FrlRate::Supported { lanes: 3, rate: 6 },
+        3 => FrlRate::Supported { lanes: 4, rate: 6 },
+        4 => FrlRate::Supported { lanes: 4, rate: 8 },
+        5 => FrlRate::Supported { lanes: 4, rate: 10 },
+        6 => FrlRate::Supported { lanes: 4, rate: 12 },
+        v => FrlRate::Reserved(v),
+    }
+}
+
+fn encode_frl_rate(rate: FrlRate) -> u8 {
+    match rate {
+        FrlRate::Unsupported => 0,
+        FrlRate::Supported { lanes: 3, rate: 3 } => 1,
+        FrlRate::Supported { lanes: 3, rate: 6 } => 2,
+        FrlRate::Supported { lanes: 4, rate: 6 } => 3,
+        FrlRate::Supported { lanes: 4, rate: 8 } => 4,
+        FrlRate::Supported { lanes: 4, rate: 10 } => 5,
+        FrlRate::Supported { lanes: 4, rate: 12 } => 6,
+        FrlRate::Supported { .. } => 0,
+        FrlRate::Reserved(v) => v & 0xF,
+    }
+}
+
+/// Maximum number of DSC slices, and maximum pixel clock per slice in MHz.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum DscMaxSlices {
+	Unsupported,
+	Slices { slices: u8, pixel_clock: u16 },
+	Reserved(u8),
+}
+
+fn dsc_max_slices(v: u8) -> DscMaxSlices {
+    match v {
+        0 => DscMaxSlices::Unsupported,
+        1 => DscMaxSlices::Slices { slices: 1, pixel_clock: 340 },
+        2 => DscMaxSlices::Slices { slices: 2, pixel_clock: 340 },
+        3 => DscMaxSlices::Slices { slices: 4, pixel_clock: 340 },
+        4 => DscMaxSlices::Slices { slices: 8, pixel_clock: 340 },
+        5 => DscMaxSlices::Slices { slices: 8, pixel_clock: 400 },
+        6 => DscMaxSlices::Slices { slices: 12, pixel_clock: 400 },
+        7 => DscMaxSlices::Slices { slices: 16, pixel_clock: 400 },
+        v => DscMaxSlices::Reserved(v),
+    }
+}
+
+fn encode_dsc_max_slices(slices: DscMaxSlices) -> u8 {
+    match slices {
+        DscMaxSlices::Unsupported => 0,
+        DscMaxSlices::Slices { slices: 1, pixel_clock: 340 } => 1,
+        DscMaxSlices::Slices { slices: 2, pixel_clock: 340 } => 2,
+        DscMaxSlices::Slices { slices: 4, pixel_clock: 340 } => 3,
+        DscMaxSlices::Slices { slices: 8, pixel_clock: 340 } => 4,
+        DscMaxSlices::Slices { slices: 8, pixel_clock: 400 } => 5,
+        DscMaxSlices::Slices { slices: 12, pixel_clock: 400 } => 6,
+        DscMaxSlices::Slices { slices: 16, pixel_clock: 400 } => 7,
+        DscMaxSlices::Slices { .. } => 0,
+        DscMaxSlices::Reserved(v) => v & 0xF,
+    }
+}
+
+/// VESA Display Stream Compression 1.2a support.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Dsc {
+	pub bpc_10: bool,
+	pub bpc_12: bool,
+	pub bpc_16: bool,
+	/// Any compressed bit rate between 8 bpp and 3 × bpc is supported.
+	pub all_bpp: bool,
+	pub native_420: bool,
+	pub max_slices: DscMaxSlices,
+	pub max_frl_rate: FrlRate,
+	/// Size of a line of chunks, in bytes.
+	pub total_chunk_bytes: u32,
+}
+
+/// HDMI Forum Sink Capability Data Structure, shared by the HF-VSDB and the HF-SCDB.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct HdmiForumCapabilities {
+	pub version: u8,
+	/// Maximum TMDS character rate in Mcsc, when above 340 Mcsc.
+	pub max_tmds_character_rate: Option<u16>,
+	pub scdc_present: bool,
+	/// The sink can initiate an SCDC read request.
+	pub rr_capable: bool,
+	pub cable_status: bool,
+	/// Color Content Bits Per Component Indication.
+	pub ccbpci: bool,
+	/// Scrambling is supported at 340 Mcsc and below.
+	pub lte_340mcsc_scramble: bool,
+	pub independent_view: bool,
+	pub dual_view: bool,
+	pub osd_disparity_3d: bool,
+	pub max_frl_rate: FrlRate,
+	/// The HDMI VICs of the HDMI VSDB are also signaled with their CTA-861 VICs.
+	pub uhd_vic: bool,
+	pub deep_color_420: DeepColor420,
+	/// Fast Vactive's start location, advance the active video after the first blanking line.
+	pub fapa_start_location: bool,
+	/// Auto Low-Latency Mode.
+	pub allm: bool,
+	/// Fast Vactive.
+	pub fva: bool,
+	/// Negative M_VRR values are supported.
+	pub cnm_vrr: bool,
+	pub cinema_vrr: bool,
+	/// The frame rate variation is limited to the M_CONST range.
+	pub m_delta: bool,
+	/// Quick Media Switching.
+	pub qms: bool,
+	pub fapa_end_extended: bool,
+	/// Minimum refresh rate in Hz for variable refresh rate.
+	pub vrr_min: Option<u8>,
+	/// Maximum refresh rate in Hz for variable refresh rate.
+	pub vrr_max: Option<u16>,
+	pub qms_tfr_min: bool,
+	pub qms_tfr_max: bool,
+	pub dsc: Option<Dsc>,
+}
+
+/// Parses the Sink Capability Data Structure, after the OUI of the HF-VSDB or the reserved
+/// bytes of the HF-SCDB.
+pub(crate) fn parse_hdmi_forum_capabilities(input: &[u8]) -> IResult<&[u8], HdmiForumCapabilities> {
+    let (rest, (version, max_tmds_character_rate, b2, b3)) = tuple((le_u8, le_u8, le_u8, le_u8))(input)?;
+
+    // The fields after the first four bytes were added by HDMI 2.1 and can be omitted
+    let b = |i: usize| input.get(i).cloned().unwrap_or(0);
+    let (b4, b5, b6, b7, b8, b9) = (b(4), b(5), b(6), b(7), b(8), b(9));
+    let rest = &rest[rest.len()..];
+
+    let vrr_max = ((b5 as u16 & 0xC0) << 2) | b6 as u16;
+    let dsc = if b7 & 0x80 != 0 {
+        Some(Dsc {
+            bpc_10: b7 & 0x01 != 0,
+            bpc_12: b7 & 0x02 != 0,
+            bpc_16: b7 & 0x04 != 0,
+            all_bpp: b7 & 0x08 != 0,
+            native_420: b7 & 0x40 != 0,
+            max_slices: dsc_max_slices(b8 & 0xF),
+            max_frl_rate: frl_rate(b8 >> 4),
+            total_chunk_bytes: ((b9 & 0x3F) as u32 + 1) * 1024,
+        })
+    } else {
+        None
+    };
+
+    Ok((
+        rest,
+        HdmiForumCapabilities {
+            version,
+            max_tmds_character_rate: if max_tmds_character_rate != 0 {
+                Some(max_tmds_character_rate as u16 * 5)
+            } else {
+                None
+            },
+            scdc_present: b2 & 0x80 != 0,
+            rr_capable: b2 & 0x40 != 0,
+            cable_status: b2 & 0x20 != 0,
+            ccbpci: b2 & 0x10 != 0,
+            lte_340mcsc_scramble: b2 & 0x08 != 0,
+            independent_view: b2 & 0x04 != 0,
+            dual_view: b2 & 0x02 != 0,
+            osd_disparity_3d: b2 & 0x01 != 0,
+            max_frl_rate: frl_rate(b3 >> 4),
+            uhd_vic: b3 & 0x08 != 0,
+            deep_color_420: DeepColor420::from_bits_truncate(b3),
+            fapa_start_location: b4 & 0x01 != 0,
+            allm: b4 & 0x02 != 0,
+            fva: b4 & 0x04 != 0,
+            cnm_vrr: b4 & 0x08 != 0,
+            cinema_vrr: b4 & 0x10 != 0,
+            m_delta: b4 & 0x20 != 0,
+            qms: b4 & 0x40 != 0,
+            fapa_end_extended: b4 & 0x80 != 0,
+            vrr_min: if b5 & 0x3F != 0 { Some(b5 & 0x3F) } else { None },
+            vrr_max: if vrr_max != 0 { Some(vrr_max) } else { None },
+            qms_tfr_min: b7 & 0x10 != 0,
+            qms_tfr_max: b7 & 0x20 != 0,
+            dsc,
+        },
+    ))
+}
+
+/// Encodes the Sink Capability Data Structure. The HDMI 2.1 fields are written up to the last one
+/// which isn't empty.
+pub(crate) fn encode_hdmi_forum_capabilities(caps: &HdmiForumCapabilities, out: &mut Vec<u8>) {
+    let flag = |v: bool, bit: u8| (v as u8) << bit;
+    let vrr_max = caps.vrr_max.unwrap_or(0);
+    let mut bytes = vec![
+        caps.version,
+        caps.max_tmds_character_rate.map_or(0, |rate| (rate / 5) as u8),
+        flag(caps.scdc_present, 7)
+            | flag(caps.rr_capable, 6)
+            | flag(caps.cable_status, 5)
+            | flag(caps.ccbpci, 4)
+            | flag(caps.lte_340mcsc_scramble, 3)
+            | flag(caps.independent_view, 2)
+            | flag(caps.dual_view, 1)
+            | flag(caps.osd_disparity_3d, 0),
+        encode_frl_rate(caps.max_frl_rate) << 4 | flag(caps.uhd_vic, 3) | caps.deep_color_420.bits(),
+        flag(caps.fapa_end_extended, 7)
+            | flag(caps.qms, 6)
+            | flag(caps.m_delta, 5)
+            | flag(caps.cinema_vrr, 4)
+            | flag(caps.cnm_vrr, 3)
+            | flag(caps.fva, 2)
+            | flag(caps.allm, 1)
+            | flag(caps.fapa_start_location, 0),
+        ((vrr_max >> 2) & 0xC0) as u8 | (caps.vrr_min.unwrap_or(0) & 0x3F),
+        vrr_max as u8,
+        flag(caps.qms_tfr_max, 5) | flag(caps.qms_tfr_min, 4),
+    ];
+    if let Some(ref dsc) = caps.dsc {
+        bytes[7] |= flag(true, 7)
+            | flag(dsc.native_420, 6)
+            | flag(dsc.all_bpp, 3)
+            | flag(dsc.bpc_16, 2)
+            | flag(dsc.bpc_12, 1)
+            | flag(dsc.bpc_10, 0);
+        bytes.push(encode_frl_rate(dsc.max_frl_rate) << 4 | encode_dsc_max_slices(dsc.max_slices));
+        bytes.push(((dsc.total_chunk_bytes / 1024).saturating_sub(1) & 0x3F) as u8);
+    }
+
+    let length = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1).max(4);
+    out.extend_from_slice(&bytes[..length]);
+}
+
+/// Parses the payload of an HF-SCDB, after the extended tag.
+pub(crate) fn parse_hdmi_forum_scdb(input: &[u8]) -> IResult<&[u8], HdmiForumCapabilities> {
+    let (input, _reserved) = take(2usize)(input)?;
+    parse_hdmi_forum_capabilities(input)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hdmi_vsdb() {
+		let d = [
+			0x10, 0x00, 0xB8, 0x3C, 0xEF, 0x0B, 0x07, 0x15, 0xFF, 0xA8, 0x45, 0x01, 0x03, 0x01, 0x41, 0x16,
+			0x08, 0x00,
+		];
+
+		let vsdb = parse_hdmi_vsdb(&d).unwrap().1;
+		assert_eq!(vsdb, HdmiVsdb {
+			physical_address: [1, 0, 0, 0],
+			supports_ai: true,
+			deep_color: HdmiDeepColor::DC_30BIT | HdmiDeepColor::DC_36BIT | HdmiDeepColor::DC_Y444,
+			dvi_dual: false,
+			max_tmds_clock: Some(300),
+			content_types: ContentTypes::all(),
+			latency: Some(Latencies {
+				video: Latency::Milliseconds(20),
+				audio: Latency::Milliseconds(12),
+			}),
+			interlaced_latency: Some(Latencies {
+				video: Latency::Milliseconds(40),
+				audio: Latency::Unsupported,
+			}),
+			video: Some(HdmiVideo {
+				stereo_3d: true,
+				image_size: ImageSize::AspectRatioOnly,
+				vics: vec![1, 3],
+				structure_all: Some(Stereo3dStructures::FRAME_PACKING | Stereo3dStructures::TOP_AND_BOTTOM | Stereo3dStructures::SIDE_BY_SIDE_HALF),
+				structure_mask: None,
+				vics_3d: vec![
+					Stereo3dVic { vic_order: 1, structure: Stereo3dStructure::TopAndBottom },
+					Stereo3dVic { vic_order: 0, structure: Stereo3dStructure::SideBySideHalf { detail: 0 } },
+				],
+			}),
+		});
+	}
+
+	#[test]
+	fn test_hdmi_vsdb_minimal() {
+		let vsdb = parse_hdmi_vsdb(&[0x21, 0x00]).unwrap().1;
+		assert_eq!(vsdb.physical_address, [2, 1, 0, 0]);
+		assert_eq!(vsdb.max_tmds_clock, None);
+		assert_eq!(vsdb.video, None);
+
+		// The latency fields are announced but missing
+		assert!(parse_hdmi_vsdb(&[0x10, 0x00, 0x00, 0x3C, 0x80]).is_err());
+	}
+
+	#[test]
+	fn test_hdmi_forum_capabilities() {
+		let d = [0x01, 0x78, 0xCA, 0x53, 0x0A, 0x30, 0x78, 0x8F, 0x25, 0x0F];
+
+		let caps = parse_hdmi_forum_capabilities(&d).unwrap().1;
+		assert_eq!(caps.max_tmds_character_rate, Some(600));
+		assert!(caps.scdc_present && caps.rr_capable && caps.lte_340mcsc_scramble && caps.dual_view);
+		assert!(!caps.independent_view && !caps.osd_disparity_3d);
+		assert_eq!(caps.max_frl_rate, FrlRate::Supported { lanes: 4, rate: 10 });
+		assert_eq!(caps.max_frl_rate.bandwidth(), Some(40));
+		assert_eq!(caps.deep_color_420, DeepColor420::DC_30BIT | DeepColor420::DC_36BIT);
+		assert!(caps.allm && caps.cnm_vrr && !caps.fva && !caps.qms);
+		assert_eq!(caps.vrr_min, Some(48));
+		assert_eq!(caps.vrr_max, Some(120));
+		assert_eq!(caps.dsc, Some(Dsc {
+			bpc_10: true,
+			bpc_12: true,
+			bpc_16: true,
+			all_bpp: true,
+			native_420: false,
+			max_slices: DscMaxSlices::Slices { slices: 8, pixel_clock: 400 },
+			max_frl_rate: FrlRate::Supported { lanes: 3, rate: 6 },
+			total_chunk_bytes: 16 * 1024,
+		}));
+		let mut out = Vec::new();
+		encode_hdmi_forum_capabilities(&caps, &mut out);
+		assert_eq!(out, d);
+
+		// HDMI 2.0 sinks stop after the deep color flags
+		let caps = parse_hdmi_forum_scdb(&[0x00, 0x00, 0x01, 0x78, 0x80, 0x00]).unwrap().1;
+		assert_eq!(caps.max_frl_rate, FrlRate::Unsupported);
+		assert_eq!((caps.vrr_min, caps.vrr_max, caps.dsc), (None, None, None));
+	}
+}
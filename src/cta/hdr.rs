@@ -0,0 +1,209 @@
+//! HDR Static Metadata and HDR Dynamic Metadata Data Blocks.
+
+use nom::bytes::complete::take;
+use nom::combinator::{all_consuming, map, opt};
+use nom::multi::many0;
+use nom::number::complete::{le_u16, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+
+bitflags! {
+	/// Electro-Optical Transfer Functions.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct Eotfs: u8 {
+		const TRADITIONAL_SDR = 1 << 0;
+		const TRADITIONAL_HDR = 1 << 1;
+		/// SMPTE ST 2084, also known as PQ.
+		const SMPTE_ST_2084 = 1 << 2;
+		/// Hybrid Log-Gamma.
+		const HLG = 1 << 3;
+	}
+}
+
+bitflags! {
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct StaticMetadataDescriptors: u8 {
+		const TYPE_1 = 1 << 0;
+	}
+}
+
+/// HDR Static Metadata Data Block, the luminances are in cd/m².
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct HdrStaticMetadata {
+	pub eotfs: Eotfs,
+	pub descriptors: StaticMetadataDescriptors,
+	pub max_luminance: Option<f32>,
+	pub max_frame_average_luminance: Option<f32>,
+	/// Only known if the maximum luminance is, which it is relative to.
+	pub min_luminance: Option<f32>,
+}
+
+pub(crate) fn luminance(v: u8) -> f32 {
+    50.0 * 2f32.powf(v as f32 / 32.0)
+}
+
+// Inverse of `luminance`
+pub(crate) fn luminance_code(l: f32) -> u8 {
+    (32.0 * (l / 50.0).log2()).round().clamp(0.0, 255.0) as u8
+}
+
+// Encodes a minimum luminance relative to the maximum one
+pub(crate) fn min_luminance_code(max: f32, min: f32) -> u8 {
+    (255.0 * (min * 100.0 / max).sqrt()).round().clamp(0.0, 255.0) as u8
+}
+
+pub(crate) fn parse_hdr_static_metadata(input: &[u8]) -> IResult<&[u8], HdrStaticMetadata> {
+    let (input, (eotfs, descriptors)) = tuple((le_u8, le_u8))(input)?;
+    // The luminance values are optional, and zero when unspecified
+    let (input, max) = opt(le_u8)(input)?;
+    let (input, max_frame_average) = opt(le_u8)(input)?;
+    let (input, min) = opt(le_u8)(input)?;
+    let nonzero = |v: Option<u8>| v.and_then(|v| if v != 0 { Some(v) } else { None });
+
+    let max_luminance = nonzero(max).map(luminance);
+    Ok((
+        input,
+        HdrStaticMetadata {
+            eotfs: Eotfs::from_bits_truncate(eotfs),
+            descriptors: StaticMetadataDescriptors::from_bits_truncate(descriptors),
+            max_luminance,
+            max_frame_average_luminance: nonzero(max_frame_average).map(luminance),
+            min_luminance: match (max_luminance, min) {
+                (Some(max), Some(min)) => Some(max * (min as f32 / 255.0).powi(2) / 100.0),
+                _ => None,
+            },
+        },
+    ))
+}
+
+/// Encodes the payload of an HDR static metadata data block, up to the last luminance which is
+/// specified.
+pub(crate) fn encode_hdr_static_metadata(hdr: &HdrStaticMetadata, out: &mut Vec<u8>) {
+    let max = hdr.max_luminance.map_or(0, luminance_code);
+    let max_frame_average = hdr.max_frame_average_luminance.map_or(0, luminance_code);
+    let min = match (hdr.max_luminance, hdr.min_luminance) {
+        (Some(max), Some(min)) => Some(min_luminance_code(luminance(luminance_code(max)), min)),
+        _ => None,
+    };
+
+    out.extend_from_slice(&[hdr.eotfs.bits(), hdr.descriptors.bits()]);
+    if let Some(min) = min {
+        out.extend_from_slice(&[max, max_frame_average, min]);
+    } else if max_frame_average != 0 {
+        out.extend_from_slice(&[max, max_frame_average]);
+    } else if max != 0 {
+        out.push(max);
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum HdrDynamicMetadataType {
+	/// Type 1, SMPTE ST 2094-10.
+	St2094_10,
+	/// Type 2, ETSI TS 103 433 (SMPTE ST 2094-20).
+	St2094_20,
+	/// Type 3, SMPTE ST 2094-30.
+	St2094_30,
+	/// Type 4, SMPTE ST 2094-40.
+	St2094_40,
+	Reserved(u16),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HdrDynamicMetadata {
+	pub kind: HdrDynamicMetadataType,
+	/// Version from the support flags, `None` if the entry has none.
+	pub version: Option<u8>,
+	/// Remaining bytes of the entry, after the support flags.
+	pub data: Vec<u8>,
+}
+
+fn parse_hdr_dynamic_metadata_entry(input: &[u8]) -> IResult<&[u8], HdrDynamicMetadata> {
+    let (input, length) = le_u8(input)?;
+    let (input, entry) = take(length)(input)?;
+    let (entry, kind) = map(le_u16, |v| match v {
+        0x0001 => HdrDynamicMetadataType::St2094_10,
+        0x0002 => HdrDynamicMetadataType::St2094_20,
+        0x0003 => HdrDynamicMetadataType::St2094_30,
+        0x0004 => HdrDynamicMetadataType::St2094_40,
+        v => HdrDynamicMetadataType::Reserved(v),
+    })(entry)?;
+    let (entry, flags) = opt(le_u8)(entry)?;
+
+    Ok((
+        input,
+        HdrDynamicMetadata {
+            kind,
+            version: flags.map(|flags| flags & 0x0F),
+            data: entry.to_vec(),
+        },
+    ))
+}
+
+pub(crate) fn parse_hdr_dynamic_metadata(input: &[u8]) -> IResult<&[u8], Vec<HdrDynamicMetadata>> {
+    all_consuming(many0(parse_hdr_dynamic_metadata_entry))(input)
+}
+
+/// Encodes the payload of an HDR dynamic metadata data block. Entries without a version nor data
+/// are written without their support flags, entries with data but no version with zero flags.
+pub(crate) fn encode_hdr_dynamic_metadata(entries: &[HdrDynamicMetadata], out: &mut Vec<u8>) {
+    for entry in entries {
+        let kind: u16 = match entry.kind {
+            HdrDynamicMetadataType::St2094_10 => 0x0001,
+            HdrDynamicMetadataType::St2094_20 => 0x0002,
+            HdrDynamicMetadataType::St2094_30 => 0x0003,
+            HdrDynamicMetadataType::St2094_40 => 0x0004,
+            HdrDynamicMetadataType::Reserved(v) => v,
+        };
+        let has_flags = entry.version.is_some() || !entry.data.is_empty();
+        out.push(2 + has_flags as u8 + entry.data.len() as u8);
+        out.extend_from_slice(&kind.to_le_bytes());
+        if has_flags {
+            out.push(entry.version.unwrap_or(0) & 0x0F);
+        }
+        out.extend_from_slice(&entry.data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hdr_static_metadata() {
+		let hdr = parse_hdr_static_metadata(&[0x0D, 0x01, 0x80, 0x60, 0x40]).unwrap().1;
+		assert_eq!(hdr.eotfs, Eotfs::TRADITIONAL_SDR | Eotfs::SMPTE_ST_2084 | Eotfs::HLG);
+		assert_eq!(hdr.descriptors, StaticMetadataDescriptors::TYPE_1);
+		assert_eq!(hdr.max_luminance, Some(800.0));
+		assert_eq!(hdr.max_frame_average_luminance, Some(400.0));
+		assert!((hdr.min_luminance.unwrap() - 0.5039).abs() < 0.0001);
+
+		let hdr = parse_hdr_static_metadata(&[0x05, 0x01]).unwrap().1;
+		assert_eq!((hdr.max_luminance, hdr.max_frame_average_luminance, hdr.min_luminance), (None, None, None));
+	}
+
+	#[test]
+	fn test_hdr_dynamic_metadata() {
+		let d = [
+			0x03, 0x01, 0x00, 0x01, // ST 2094-10 version 1
+			0x03, 0x04, 0x00, 0x01, // ST 2094-40 version 1
+			0x04, 0x02, 0x00, 0x00, 0x0F, // ST 2094-20 with additional data
+			0x03, 0x03, 0x00, 0x00, // ST 2094-30 version 0
+			0x02, 0x03, 0x00, // ST 2094-30 without support flags
+		];
+
+		let entries = parse_hdr_dynamic_metadata(&d).unwrap().1;
+		assert_eq!(entries, vec![
+			HdrDynamicMetadata { kind: HdrDynamicMetadataType::St2094_10, version: Some(1), data: vec![] },
+			HdrDynamicMetadata { kind: HdrDynamicMetadataType::St2094_40, version: Some(1), data: vec![] },
+			HdrDynamicMetadata { kind: HdrDynamicMetadataType::St2094_20, version: Some(0), data: vec![0x0F] },
+			HdrDynamicMetadata { kind: HdrDynamicMetadataType::St2094_30, version: Some(0), data: vec![] },
+			HdrDynamicMetadata { kind: HdrDynamicMetadataType::St2094_30, version: None, data: vec![] },
+		]);
+		let mut out = Vec::new();
+		encode_hdr_dynamic_metadata(&entries, &mut out);
+		assert_eq!(out, d);
+
+		assert!(parse_hdr_dynamic_metadata(&d[..6]).is_err());
+	}
+}
@@ -0,0 +1,490 @@
+//! Dolby Vision and HDR10+ vendor-specific video data blocks, and AMD FreeSync vendor-specific
+//! data block.
+
+use nom::bytes::complete::take;
+use nom::combinator::map;
+use nom::number::complete::le_u8;
+use nom::IResult;
+
+use super::super::ChromaticityPoint;
+use super::hdr::{luminance, luminance_code, min_luminance_code};
+
+/// IEEE OUI of Dolby Laboratories.
+pub const DOLBY_OUI: u32 = 0x00D046;
+/// IEEE OUI of HDR10+ Technologies, LLC.
+pub const HDR10_PLUS_OUI: u32 = 0x90848B;
+/// IEEE OUI of Advanced Micro Devices.
+pub const AMD_OUI: u32 = 0x00001A;
+
+// Converts a 12-bit SMPTE ST 2084 code value to cd/m²
+fn pq_luminance(v: u16) -> f32 {
+    let (m1, m2) = (0.159_301_76_f32, 78.843_75_f32);
+    let (c1, c2, c3) = (0.835_937_5_f32, 18.851_562_f32, 18.6875_f32);
+    let e = (v as f32 / 4095.0).powf(1.0 / m2);
+    10000.0 * ((e - c1).max(0.0) / (c2 - c3 * e)).powf(1.0 / m1)
+}
+
+// Inverse of `pq_luminance`
+fn pq_code(l: f32) -> u16 {
+    let (m1, m2) = (0.159_301_76_f32, 78.843_75_f32);
+    let (c1, c2, c3) = (0.835_937_5_f32, 18.851_562_f32, 18.6875_f32);
+    let y = (l / 10000.0).max(0.0).powf(m1);
+    (4095.0 * ((c1 + c2 * y) / (1.0 + c3 * y)).powf(m2)).round().min(4095.0) as u16
+}
+
+fn point(x: f64, y: f64) -> ChromaticityPoint {
+    ChromaticityPoint { x, y }
+}
+
+/// Dolby Vision interfaces, all versions support the low-latency one.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum DolbyVisionInterface {
+	LowLatency,
+	LowLatencyHdmi,
+	StandardLowLatency,
+	StandardLowLatencyHdmi,
+}
+
+/// Bit depths supported in 4:4:4.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum DolbyVision444 {
+	Unsupported,
+	Bits10,
+	Bits12,
+	Reserved,
+}
+
+/// Version 0 of the Dolby Vision VSVDB.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DolbyVisionV0 {
+	pub yuv422_12bit: bool,
+	pub supports_2160p60: bool,
+	pub global_dimming: bool,
+	pub red: ChromaticityPoint,
+	pub green: ChromaticityPoint,
+	pub blue: ChromaticityPoint,
+	pub white: ChromaticityPoint,
+	/// Target luminances in cd/m².
+	pub target_min_luminance: f32,
+	pub target_max_luminance: f32,
+	/// Display management version, major and minor.
+	pub dm_version: (u8, u8),
+}
+
+/// Version 1 of the Dolby Vision VSVDB, the white point is D65.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DolbyVisionV1 {
+	pub yuv422_12bit: bool,
+	pub supports_2160p60: bool,
+	pub global_dimming: bool,
+	/// Display management major version.
+	pub dm_version: u8,
+	/// The colorimetry is P3-D65 rather than BT.709.
+	pub colorimetry_p3: bool,
+	/// The low-latency interface is supported as well as the standard one.
+	pub low_latency: bool,
+	/// Target luminances in cd/m².
+	pub target_min_luminance: f32,
+	pub target_max_luminance: f32,
+	pub red: ChromaticityPoint,
+	pub green: ChromaticityPoint,
+	pub blue: ChromaticityPoint,
+}
+
+/// Version 2 of the Dolby Vision VSVDB, the white point is D65.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DolbyVisionV2 {
+	pub yuv422_12bit: bool,
+	pub backlight_control: bool,
+	pub global_dimming: bool,
+	/// Display management major version.
+	pub dm_version: u8,
+	/// Minimum backlight luminance in cd/m².
+	pub backlight_min_luminance: u16,
+	pub interface: DolbyVisionInterface,
+	pub rgb444: DolbyVision444,
+	/// Target luminances in cd/m².
+	pub target_min_luminance: f32,
+	pub target_max_luminance: f32,
+	pub red: ChromaticityPoint,
+	pub green: ChromaticityPoint,
+	pub blue: ChromaticityPoint,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum DolbyVision {
+	V0(DolbyVisionV0),
+	V1(DolbyVisionV1),
+	V2(DolbyVisionV2),
+	/// Unknown version, with the payload after the OUI.
+	Unknown(u8, Vec<u8>),
+}
+
+fn dolby_vision_v0(x: &[u8]) -> DolbyVisionV0 {
+    let coordinate = |lo: u8, hi: u8| ((hi as u16) << 4 | lo as u16) as f64 / 4096.0;
+    let primary = |i: usize| {
+        point(
+            coordinate(x[i] >> 4, x[i + 1]),
+            coordinate(x[i] & 0xF, x[i + 2]),
+        )
+    };
+    DolbyVisionV0 {
+        yuv422_12bit: x[0] & 0x01 != 0,
+        supports_2160p60: x[0] & 0x02 != 0,
+        global_dimming: x[0] & 0x04 != 0,
+        red: primary(1),
+        green: primary(4),
+        blue: primary(7),
+        white: primary(10),
+        target_min_luminance: pq_luminance((x[14] as u16) << 4 | (x[13] >> 4) as u16),
+        target_max_luminance: pq_luminance((x[15] as u16) << 4 | (x[13] & 0xF) as u16),
+        dm_version: (x[16] >> 4, x[16] & 0xF),
+    }
+}
+
+fn dolby_vision_v1(x: &[u8]) -> DolbyVisionV1 {
+    let min = (x[2] >> 1) as f32 / 127.0;
+    // The short form of the block packs the primaries into the ranges they can be in
+    let (red, green, blue) = if x.len() >= 10 {
+        let primary = |i: usize| point(x[i] as f64 / 256.0, x[i + 1] as f64 / 256.0);
+        (primary(4), primary(6), primary(8))
+    } else {
+        let red_y = ((x[6] & 0x7) << 2 | (x[5] & 0x1) << 1 | (x[4] & 0x1)) as f64;
+        (
+            point(0.625 + (x[6] >> 3) as f64 / 256.0, 0.25 + red_y / 256.0),
+            point((x[4] >> 1) as f64 / 256.0, 0.5 + (x[5] >> 1) as f64 / 256.0),
+            point(
+                0.125 + (x[3] >> 5) as f64 / 256.0,
+                0.03125 + ((x[3] >> 2) & 0x7) as f64 / 256.0,
+            ),
+        )
+    };
+    DolbyVisionV1 {
+        yuv422_12bit: x[0] & 0x01 != 0,
+        supports_2160p60: x[0] & 0x02 != 0,
+        global_dimming: x[1] & 0x01 != 0,
+        dm_version: ((x[0] >> 2) & 0x7) + 2,
+        colorimetry_p3: x[2] & 0x01 != 0,
+        low_latency: x[3] & 0x01 != 0,
+        target_min_luminance: min * min,
+        target_max_luminance: 100.0 + (x[1] >> 1) as f32 * 50.0,
+        red,
+        green,
+        blue,
+    }
+}
+
+fn dolby_vision_v2(x: &[u8]) -> DolbyVisionV2 {
+    DolbyVisionV2 {
+        yuv422_12bit: x[0] & 0x01 != 0,
+        backlight_control: x[0] & 0x02 != 0,
+        global_dimming: x[1] & 0x04 != 0,
+        dm_version: ((x[0] >> 2) & 0x7) + 2,
+        backlight_min_luminance: 25 + (x[1] & 0x3) as u16 * 25,
+        interface: match x[2] & 0x3 {
+            0 => DolbyVisionInterface::LowLatency,
+            1 => DolbyVisionInterface::LowLatencyHdmi,
+            2 => DolbyVisionInterface::StandardLowLatency,
+            _ => DolbyVisionInterface::StandardLowLatencyHdmi,
+        },
+        rgb444: match (x[3] & 0x1) << 1 | (x[4] & 0x1) {
+            0 => DolbyVision444::Unsupported,
+            1 => DolbyVision444::Bits10,
+            2 => DolbyVision444::Bits12,
+            _ => DolbyVision444::Reserved,
+        },
+        target_min_luminance: pq_luminance(20 * (x[1] >> 3) as u16),
+        target_max_luminance: pq_luminance(2055 + 65 * (x[2] >> 3) as u16),
+        red: point(0.625 + (x[5] >> 3) as f64 / 256.0, 0.25 + (x[6] >> 3) as f64 / 256.0),
+        green: point((x[3] >> 1) as f64 / 256.0, 0.5 + (x[4] >> 1) as f64 / 256.0),
+        blue: point(
+            0.125 + (x[5] & 0x7) as f64 / 256.0,
+            0.03125 + (x[6] & 0x7) as f64 / 256.0,
+        ),
+    }
+}
+
+// Returns the code of a coordinate stored in 1/256 steps from `base`, if it is exactly
+// representable in `bits` bits
+fn offset_code(v: f64, base: f64, bits: u32) -> Option<u8> {
+    let code = (v - base) * 256.0;
+    if code >= 0.0 && code < (1 << bits) as f64 && code.fract() == 0.0 {
+        Some(code as u8)
+    } else {
+        None
+    }
+}
+
+fn clamped_offset_code(v: f64, base: f64, bits: u32) -> u8 {
+    ((v - base) * 256.0).round().clamp(0.0, ((1 << bits) - 1) as f64) as u8
+}
+
+fn encode_dolby_vision_v0(v: &DolbyVisionV0) -> Vec<u8> {
+    let coordinate = |c: f64| (c * 4096.0).round().clamp(0.0, 4095.0) as u16;
+    let mut x = vec![(v.global_dimming as u8) << 2 | (v.supports_2160p60 as u8) << 1 | v.yuv422_12bit as u8];
+    for p in &[v.red, v.green, v.blue, v.white] {
+        let (px, py) = (coordinate(p.x), coordinate(p.y));
+        x.extend_from_slice(&[((px & 0xF) << 4 | (py & 0xF)) as u8, (px >> 4) as u8, (py >> 4) as u8]);
+    }
+    let (min, max) = (pq_code(v.target_min_luminance), pq_code(v.target_max_luminance));
+    x.extend_from_slice(&[
+        ((min & 0xF) << 4 | (max & 0xF)) as u8,
+        (min >> 4) as u8,
+        (max >> 4) as u8,
+        v.dm_version.0 << 4 | (v.dm_version.1 & 0xF),
+    ]);
+    x
+}
+
+fn encode_dolby_vision_v1(v: &DolbyVisionV1) -> Vec<u8> {
+    let max = ((v.target_max_luminance - 100.0) / 50.0).round().clamp(0.0, 127.0) as u8;
+    let min = (v.target_min_luminance.sqrt() * 127.0).round().clamp(0.0, 127.0) as u8;
+    let mut x = vec![
+        1 << 5
+            | (v.dm_version.saturating_sub(2) & 0x7) << 2
+            | (v.supports_2160p60 as u8) << 1
+            | v.yuv422_12bit as u8,
+        max << 1 | v.global_dimming as u8,
+        min << 1 | v.colorimetry_p3 as u8,
+    ];
+
+    // Use the short form when the primaries fit in it
+    let short = (
+        offset_code(v.red.x, 0.625, 5),
+        offset_code(v.red.y, 0.25, 5),
+        offset_code(v.green.x, 0.0, 7),
+        offset_code(v.green.y, 0.5, 7),
+        offset_code(v.blue.x, 0.125, 3),
+        offset_code(v.blue.y, 0.03125, 3),
+    );
+    if let (Some(rx), Some(ry), Some(gx), Some(gy), Some(bx), Some(by)) = short {
+        x.extend_from_slice(&[
+            bx << 5 | by << 2 | v.low_latency as u8,
+            gx << 1 | (ry & 0x1),
+            gy << 1 | ((ry >> 1) & 0x1),
+            rx << 3 | ry >> 2,
+        ]);
+    } else {
+        x.push(v.low_latency as u8);
+        for p in &[v.red, v.green, v.blue] {
+            x.extend_from_slice(&[clamped_offset_code(p.x, 0.0, 8), clamped_offset_code(p.y, 0.0, 8)]);
+        }
+    }
+    x
+}
+
+fn encode_dolby_vision_v2(v: &DolbyVisionV2) -> Vec<u8> {
+    let min = (pq_code(v.target_min_luminance) / 20).min(31) as u8;
+    let max = (pq_code(v.target_max_luminance).saturating_sub(2055) / 65).min(31) as u8;
+    let interface = match v.interface {
+        DolbyVisionInterface::LowLatency => 0,
+        DolbyVisionInterface::LowLatencyHdmi => 1,
+        DolbyVisionInterface::StandardLowLatency => 2,
+        DolbyVisionInterface::StandardLowLatencyHdmi => 3,
+    };
+    let rgb444 = match v.rgb444 {
+        DolbyVision444::Unsupported => 0,
+        DolbyVision444::Bits10 => 1,
+        DolbyVision444::Bits12 => 2,
+        DolbyVision444::Reserved => 3,
+    };
+    let backlight_min = (v.backlight_min_luminance.saturating_sub(25) / 25).min(3) as u8;
+    vec![
+        2 << 5
+            | (v.dm_version.saturating_sub(2) & 0x7) << 2
+            | (v.backlight_control as u8) << 1
+            | v.yuv422_12bit as u8,
+        min << 3 | (v.global_dimming as u8) << 2 | backlight_min,
+        max << 3 | interface,
+        clamped_offset_code(v.green.x, 0.0, 7) << 1 | rgb444 >> 1,
+        clamped_offset_code(v.green.y, 0.5, 7) << 1 | (rgb444 & 0x1),
+        clamped_offset_code(v.red.x, 0.625, 5) << 3 | clamped_offset_code(v.blue.x, 0.125, 3),
+        clamped_offset_code(v.red.y, 0.25, 5) << 3 | clamped_offset_code(v.blue.y, 0.03125, 3),
+    ]
+}
+
+/// Parses the payload of a Dolby Vision VSVDB, after the OUI.
+pub(crate) fn parse_dolby_vision(input: &[u8]) -> IResult<&[u8], DolbyVision> {
+    let version = input.first().map_or(0, |b| b >> 5);
+    let length = match version {
+        0 => 17,
+        1 if input.len() >= 10 => 10,
+        1 | 2 => 7,
+        _ => input.len(),
+    };
+    let (rest, x) = take(length)(input)?;
+    let vision = match version {
+        0 => DolbyVision::V0(dolby_vision_v0(x)),
+        1 => DolbyVision::V1(dolby_vision_v1(x)),
+        2 => DolbyVision::V2(dolby_vision_v2(x)),
+        _ => DolbyVision::Unknown(version, x.to_vec()),
+    };
+    Ok((rest, vision))
+}
+
+/// Encodes the payload of a Dolby Vision VSVDB, after the OUI.
+pub(crate) fn encode_dolby_vision(vision: &DolbyVision, out: &mut Vec<u8>) {
+    match *vision {
+        DolbyVision::V0(ref v) => out.extend(encode_dolby_vision_v0(v)),
+        DolbyVision::V1(ref v) => out.extend(encode_dolby_vision_v1(v)),
+        DolbyVision::V2(ref v) => out.extend(encode_dolby_vision_v2(v)),
+        DolbyVision::Unknown(_, ref payload) => out.extend_from_slice(payload),
+    }
+}
+
+/// HDR10+ VSVDB.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Hdr10Plus {
+	pub application_version: u8,
+}
+
+/// Parses the payload of an HDR10+ VSVDB, after the OUI.
+pub(crate) fn parse_hdr10_plus(input: &[u8]) -> IResult<&[u8], Hdr10Plus> {
+    map(le_u8, |b| Hdr10Plus { application_version: b & 0x3 })(input)
+}
+
+pub(crate) fn encode_hdr10_plus(hdr10_plus: &Hdr10Plus, out: &mut Vec<u8>) {
+    out.push(hdr10_plus.application_version & 0x3);
+}
+
+/// FreeSync Premium Pro (FreeSync 2) fields, the luminances are in cd/m².
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct FreeSyncPremiumPro {
+	pub flags: u8,
+	pub local_dimming: bool,
+	pub max_luminance: f32,
+	pub min_luminance: f32,
+	/// Luminance range with local dimming disabled, if it has local dimming.
+	pub max_luminance_without_local_dimming: Option<f32>,
+	pub min_luminance_without_local_dimming: Option<f32>,
+}
+
+/// AMD FreeSync VSDB, whose layout isn't public and was inferred from existing EDIDs.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct FreeSync {
+	pub version: (u8, u8),
+	/// Variable refresh rate range in Hz.
+	pub min_refresh_rate: u8,
+	pub max_refresh_rate: u8,
+	pub flags: u8,
+	pub premium_pro: Option<FreeSyncPremiumPro>,
+}
+
+/// Parses the payload of an AMD VSDB, after the OUI.
+pub(crate) fn parse_freesync(input: &[u8]) -> IResult<&[u8], FreeSync> {
+    let (rest, x) = take(5usize)(input)?;
+    let (rest, premium_pro) = if rest.len() >= 5 {
+        let (rest, y) = take(5usize)(rest)?;
+        let max_luminance = luminance(y[1]);
+        let min_luminance = |max: f32, min: u8| max * (min as f32 / 255.0).powi(2) / 100.0;
+        let local_dimming = y[0] & 0x04 != 0;
+        (
+            rest,
+            Some(FreeSyncPremiumPro {
+                flags: y[0],
+                local_dimming,
+                max_luminance,
+                min_luminance: min_luminance(max_luminance, y[2]),
+                max_luminance_without_local_dimming: if local_dimming { Some(luminance(y[3])) } else { None },
+                min_luminance_without_local_dimming: if local_dimming {
+                    Some(min_luminance(luminance(y[3]), y[4]))
+                } else {
+                    None
+                },
+            }),
+        )
+    } else {
+        (rest, None)
+    };
+
+    Ok((
+        rest,
+        FreeSync {
+            version: (x[0], x[1]),
+            min_refresh_rate: x[2],
+            max_refresh_rate: x[3],
+            flags: x[4],
+            premium_pro,
+        },
+    ))
+}
+
+/// Encodes the payload of an AMD VSDB, after the OUI.
+pub(crate) fn encode_freesync(freesync: &FreeSync, out: &mut Vec<u8>) {
+    out.extend_from_slice(&[
+        freesync.version.0,
+        freesync.version.1,
+        freesync.min_refresh_rate,
+        freesync.max_refresh_rate,
+        freesync.flags,
+    ]);
+    if let Some(ref pro) = freesync.premium_pro {
+        let max = luminance_code(pro.max_luminance);
+        let max_without_local_dimming = pro.max_luminance_without_local_dimming.map_or(0, luminance_code);
+        out.extend_from_slice(&[
+            (pro.flags & !0x04) | (pro.local_dimming as u8) << 2,
+            max,
+            min_luminance_code(luminance(max), pro.min_luminance),
+            max_without_local_dimming,
+            pro.min_luminance_without_local_dimming.map_or(0, |min| {
+                min_luminance_code(luminance(max_without_local_dimming), min)
+            }),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_dolby_vision() {
+		let v1 = [0x2E, 0x5C, 0x9B, 0x69, 0x4A, 0x9F, 0xB1];
+		match parse_dolby_vision(&v1).unwrap().1 {
+			DolbyVision::V1(v) => {
+				assert!(!v.yuv422_12bit && v.supports_2160p60 && !v.global_dimming);
+				assert_eq!(v.dm_version, 5);
+				assert!(v.colorimetry_p3 && v.low_latency);
+				assert_eq!(v.target_max_luminance, 2400.0);
+				assert_eq!(v.red, point(0.625 + 22.0 / 256.0, 0.25 + 6.0 / 256.0));
+				assert_eq!(v.green, point(37.0 / 256.0, 0.5 + 79.0 / 256.0));
+				assert_eq!(v.blue, point(0.125 + 3.0 / 256.0, 0.03125 + 2.0 / 256.0));
+			}
+			v => panic!("unexpected {:?}", v),
+		}
+
+		let v2 = [0x45, 0x41, 0x7E, 0x47, 0x9C, 0x6A, 0x3B];
+		match parse_dolby_vision(&v2).unwrap().1 {
+			DolbyVision::V2(v) => {
+				assert!(v.yuv422_12bit && !v.backlight_control);
+				assert_eq!(v.backlight_min_luminance, 50);
+				assert_eq!(v.interface, DolbyVisionInterface::StandardLowLatency);
+				assert_eq!(v.rgb444, DolbyVision444::Bits12);
+				assert!((v.target_min_luminance - pq_luminance(160)).abs() < 0.0001);
+				assert!((v.target_max_luminance - 896.54).abs() < 0.01);
+			}
+			v => panic!("unexpected {:?}", v),
+		}
+
+		assert!(parse_dolby_vision(&v1[..3]).is_err());
+	}
+
+	#[test]
+	fn test_freesync() {
+		let freesync = parse_freesync(&[0x01, 0x00, 0x30, 0x90, 0x00]).unwrap().1;
+		assert_eq!((freesync.min_refresh_rate, freesync.max_refresh_rate), (48, 144));
+		assert_eq!(freesync.premium_pro, None);
+
+		let freesync = parse_freesync(&[0x02, 0x00, 0x30, 0x90, 0x00, 0x07, 0xA0, 0x40, 0x60, 0x20]).unwrap().1;
+		let premium_pro = freesync.premium_pro.unwrap();
+		assert!(premium_pro.local_dimming);
+		assert_eq!(premium_pro.max_luminance, 1600.0);
+		assert_eq!(premium_pro.max_luminance_without_local_dimming, Some(400.0));
+	}
+
+	#[test]
+	fn test_pq_luminance() {
+		assert_eq!(pq_luminance(0), 0.0);
+		assert!((pq_luminance(4095) - 10000.0).abs() < 1.0);
+	}
+}
@@ -0,0 +1,238 @@
+//! CTA-861 video formats, identified by their Video Identification Code (VIC).
+
+use super::super::{mode_timing, DetailedTiming, ModeLine};
+
+/// Picture aspect ratio of a video format.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum PictureAspectRatio {
+	Ratio4_3,
+	Ratio16_9,
+	Ratio64_27,
+	Ratio256_135,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct VideoFormat {
+	pub vic: u8,
+	/// Vertical values are per field for interlaced formats, like in detailed timings.
+	pub timing: DetailedTiming,
+	pub interlaced: bool,
+	/// Nominal vertical (field) rate in Hz. The 1000/1001 variant is also allowed for multiples
+	/// of 24 and 30 Hz.
+	pub refresh_rate: u16,
+	pub picture_aspect_ratio: PictureAspectRatio,
+	/// Allowed pixel repetition factors, 1 meaning each pixel is sent once.
+	pub pixel_repetition: &'static [u8],
+}
+
+type VideoFormatEntry = (u8, u32, ModeLine, ModeLine, u8, u16, PictureAspectRatio, &'static [u8]);
+
+// (VIC, pixel clock in kHz, horizontal, vertical, features, refresh rate, picture aspect ratio,
+// pixel repetition), from CTA-861-H. Interlaced vertical values are per field.
+const VIDEO_FORMATS: &[VideoFormatEntry] = &[
+	(1, 25175, (640, 800, 16, 96), (480, 525, 10, 2), 0x18, 60, PictureAspectRatio::Ratio4_3, &[1]),
+	(2, 27000, (720, 858, 16, 62), (480, 525, 9, 6), 0x18, 60, PictureAspectRatio::Ratio4_3, &[1]),
+	(3, 27000, (720, 858, 16, 62), (480, 525, 9, 6), 0x18, 60, PictureAspectRatio::Ratio16_9, &[1]),
+	(4, 74250, (1280, 1650, 110, 40), (720, 750, 5, 5), 0x1E, 60, PictureAspectRatio::Ratio16_9, &[1]),
+	(5, 74250, (1920, 2200, 88, 44), (540, 562, 2, 5), 0x9E, 60, PictureAspectRatio::Ratio16_9, &[1]),
+	(6, 27000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x98, 60, PictureAspectRatio::Ratio4_3, &[2]),
+	(7, 27000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x98, 60, PictureAspectRatio::Ratio16_9, &[2]),
+	(8, 27000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x18, 60, PictureAspectRatio::Ratio4_3, &[2]),
+	(9, 27000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x18, 60, PictureAspectRatio::Ratio16_9, &[2]),
+	(10, 54000, (2880, 3432, 76, 248), (240, 262, 4, 3), 0x98, 60, PictureAspectRatio::Ratio4_3, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(11, 54000, (2880, 3432, 76, 248), (240, 262, 4, 3), 0x98, 60, PictureAspectRatio::Ratio16_9, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(12, 54000, (2880, 3432, 76, 248), (240, 262, 4, 3), 0x18, 60, PictureAspectRatio::Ratio4_3, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(13, 54000, (2880, 3432, 76, 248), (240, 262, 4, 3), 0x18, 60, PictureAspectRatio::Ratio16_9, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(14, 54000, (1440, 1716, 32, 124), (480, 525, 9, 6), 0x18, 60, PictureAspectRatio::Ratio4_3, &[1, 2]),
+	(15, 54000, (1440, 1716, 32, 124), (480, 525, 9, 6), 0x18, 60, PictureAspectRatio::Ratio16_9, &[1, 2]),
+	(16, 148500, (1920, 2200, 88, 44), (1080, 1125, 4, 5), 0x1E, 60, PictureAspectRatio::Ratio16_9, &[1]),
+	(17, 27000, (720, 864, 12, 64), (576, 625, 5, 5), 0x18, 50, PictureAspectRatio::Ratio4_3, &[1]),
+	(18, 27000, (720, 864, 12, 64), (576, 625, 5, 5), 0x18, 50, PictureAspectRatio::Ratio16_9, &[1]),
+	(19, 74250, (1280, 1980, 440, 40), (720, 750, 5, 5), 0x1E, 50, PictureAspectRatio::Ratio16_9, &[1]),
+	(20, 74250, (1920, 2640, 528, 44), (540, 562, 2, 5), 0x9E, 50, PictureAspectRatio::Ratio16_9, &[1]),
+	(21, 27000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x98, 50, PictureAspectRatio::Ratio4_3, &[2]),
+	(22, 27000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x98, 50, PictureAspectRatio::Ratio16_9, &[2]),
+	(23, 27000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x18, 50, PictureAspectRatio::Ratio4_3, &[2]),
+	(24, 27000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x18, 50, PictureAspectRatio::Ratio16_9, &[2]),
+	(25, 54000, (2880, 3456, 48, 252), (288, 312, 2, 3), 0x98, 50, PictureAspectRatio::Ratio4_3, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(26, 54000, (2880, 3456, 48, 252), (288, 312, 2, 3), 0x98, 50, PictureAspectRatio::Ratio16_9, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(27, 54000, (2880, 3456, 48, 252), (288, 312, 2, 3), 0x18, 50, PictureAspectRatio::Ratio4_3, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(28, 54000, (2880, 3456, 48, 252), (288, 312, 2, 3), 0x18, 50, PictureAspectRatio::Ratio16_9, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+	(29, 54000, (1440, 1728, 24, 128), (576, 625, 5, 5), 0x18, 50, PictureAspectRatio::Ratio4_3, &[1, 2]),
+	(30, 54000, (1440, 1728, 24, 128), (576, 625, 5, 5), 0x18, 50, PictureAspectRatio::Ratio16_9, &[1, 2]),
+	(31, 148500, (1920, 2640, 528, 44), (1080, 1125, 4, 5), 0x1E, 50, PictureAspectRatio::Ratio16_9, &[1]),
+	(32, 74250, (1920, 2750, 638, 44), (1080, 1125, 4, 5), 0x1E, 24, PictureAspectRatio::Ratio16_9, &[1]),
+	(33, 74250, (1920, 2640, 528, 44), (1080, 1125, 4, 5), 0x1E, 25, PictureAspectRatio::Ratio16_9, &[1]),
+	(34, 74250, (1920, 2200, 88, 44), (1080, 1125, 4, 5), 0x1E, 30, PictureAspectRatio::Ratio16_9, &[1]),
+	(35, 108000, (2880, 3432, 64, 248), (480, 525, 9, 6), 0x18, 60, PictureAspectRatio::Ratio4_3, &[1, 2, 4]),
+	(36, 108000, (2880, 3432, 64, 248), (480, 525, 9, 6), 0x18, 60, PictureAspectRatio::Ratio16_9, &[1, 2, 4]),
+	(37, 108000, (2880, 3456, 48, 256), (576, 625, 5, 5), 0x18, 50, PictureAspectRatio::Ratio4_3, &[1, 2, 4]),
+	(38, 108000, (2880, 3456, 48, 256), (576, 625, 5, 5), 0x18, 50, PictureAspectRatio::Ratio16_9, &[1, 2, 4]),
+	(39, 72000, (1920, 2304, 32, 168), (540, 625, 23, 5), 0x9A, 50, PictureAspectRatio::Ratio16_9, &[1]),
+	(40, 148500, (1920, 2640, 528, 44), (540, 562, 2, 5), 0x9E, 100, PictureAspectRatio::Ratio16_9, &[1]),
+	(41, 148500, (1280, 1980, 440, 40), (720, 750, 5, 5), 0x1E, 100, PictureAspectRatio::Ratio16_9, &[1]),
+	(42, 54000, (720, 864, 12, 64), (576, 625, 5, 5), 0x18, 100, PictureAspectRatio::Ratio4_3, &[1]),
+	(43, 54000, (720, 864, 12, 64), (576, 625, 5, 5), 0x18, 100, PictureAspectRatio::Ratio16_9, &[1]),
+	(44, 54000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x98, 100, PictureAspectRatio::Ratio4_3, &[2]),
+	(45, 54000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x98, 100, PictureAspectRatio::Ratio16_9, &[2]),
+	(46, 148500, (1920, 2200, 88, 44), (540, 562, 2, 5), 0x9E, 120, PictureAspectRatio::Ratio16_9, &[1]),
+	(47, 148500, (1280, 1650, 110, 40), (720, 750, 5, 5), 0x1E, 120, PictureAspectRatio::Ratio16_9, &[1]),
+	(48, 54000, (720, 858, 16, 62), (480, 525, 9, 6), 0x18, 120, PictureAspectRatio::Ratio4_3, &[1]),
+	(49, 54000, (720, 858, 16, 62), (480, 525, 9, 6), 0x18, 120, PictureAspectRatio::Ratio16_9, &[1]),
+	(50, 54000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x98, 120, PictureAspectRatio::Ratio4_3, &[2]),
+	(51, 54000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x98, 120, PictureAspectRatio::Ratio16_9, &[2]),
+	(52, 108000, (720, 864, 12, 64), (576, 625, 5, 5), 0x18, 200, PictureAspectRatio::Ratio4_3, &[1]),
+	(53, 108000, (720, 864, 12, 64), (576, 625, 5, 5), 0x18, 200, PictureAspectRatio::Ratio16_9, &[1]),
+	(54, 108000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x98, 200, PictureAspectRatio::Ratio4_3, &[2]),
+	(55, 108000, (1440, 1728, 24, 126), (288, 312, 2, 3), 0x98, 200, PictureAspectRatio::Ratio16_9, &[2]),
+	(56, 108000, (720, 858, 16, 62), (480, 525, 9, 6), 0x18, 240, PictureAspectRatio::Ratio4_3, &[1]),
+	(57, 108000, (720, 858, 16, 62), (480, 525, 9, 6), 0x18, 240, PictureAspectRatio::Ratio16_9, &[1]),
+	(58, 108000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x98, 240, PictureAspectRatio::Ratio4_3, &[2]),
+	(59, 108000, (1440, 1716, 38, 124), (240, 262, 4, 3), 0x98, 240, PictureAspectRatio::Ratio16_9, &[2]),
+	(60, 59400, (1280, 3300, 1760, 40), (720, 750, 5, 5), 0x1E, 24, PictureAspectRatio::Ratio16_9, &[1]),
+	(61, 74250, (1280, 3960, 2420, 40), (720, 750, 5, 5), 0x1E, 25, PictureAspectRatio::Ratio16_9, &[1]),
+	(62, 74250, (1280, 3300, 1760, 40), (720, 750, 5, 5), 0x1E, 30, PictureAspectRatio::Ratio16_9, &[1]),
+	(63, 297000, (1920, 2200, 88, 44), (1080, 1125, 4, 5), 0x1E, 120, PictureAspectRatio::Ratio16_9, &[1]),
+	(64, 297000, (1920, 2640, 528, 44), (1080, 1125, 4, 5), 0x1E, 100, PictureAspectRatio::Ratio16_9, &[1]),
+	(65, 59400, (1280, 3300, 1760, 40), (720, 750, 5, 5), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(66, 74250, (1280, 3960, 2420, 40), (720, 750, 5, 5), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(67, 74250, (1280, 3300, 1760, 40), (720, 750, 5, 5), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(68, 74250, (1280, 1980, 440, 40), (720, 750, 5, 5), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(69, 74250, (1280, 1650, 110, 40), (720, 750, 5, 5), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(70, 148500, (1280, 1980, 440, 40), (720, 750, 5, 5), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(71, 148500, (1280, 1650, 110, 40), (720, 750, 5, 5), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(72, 74250, (1920, 2750, 638, 44), (1080, 1125, 4, 5), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(73, 74250, (1920, 2640, 528, 44), (1080, 1125, 4, 5), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(74, 74250, (1920, 2200, 88, 44), (1080, 1125, 4, 5), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(75, 148500, (1920, 2640, 528, 44), (1080, 1125, 4, 5), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(76, 148500, (1920, 2200, 88, 44), (1080, 1125, 4, 5), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(77, 297000, (1920, 2640, 528, 44), (1080, 1125, 4, 5), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(78, 297000, (1920, 2200, 88, 44), (1080, 1125, 4, 5), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(79, 59400, (1680, 3300, 1360, 40), (720, 750, 5, 5), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(80, 59400, (1680, 3168, 1228, 40), (720, 750, 5, 5), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(81, 59400, (1680, 2640, 700, 40), (720, 750, 5, 5), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(82, 82500, (1680, 2200, 260, 40), (720, 750, 5, 5), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(83, 99000, (1680, 2200, 260, 40), (720, 750, 5, 5), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(84, 165000, (1680, 2000, 60, 40), (720, 825, 5, 5), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(85, 198000, (1680, 2000, 60, 40), (720, 825, 5, 5), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(86, 99000, (2560, 3750, 998, 44), (1080, 1100, 4, 5), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(87, 90000, (2560, 3200, 448, 44), (1080, 1125, 4, 5), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(88, 118800, (2560, 3520, 768, 44), (1080, 1125, 4, 5), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(89, 185625, (2560, 3300, 548, 44), (1080, 1125, 4, 5), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(90, 198000, (2560, 3000, 248, 44), (1080, 1100, 4, 5), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(91, 371250, (2560, 2970, 218, 44), (1080, 1250, 4, 5), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(92, 495000, (2560, 3300, 548, 44), (1080, 1250, 4, 5), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(93, 297000, (3840, 5500, 1276, 88), (2160, 2250, 8, 10), 0x1E, 24, PictureAspectRatio::Ratio16_9, &[1]),
+	(94, 297000, (3840, 5280, 1056, 88), (2160, 2250, 8, 10), 0x1E, 25, PictureAspectRatio::Ratio16_9, &[1]),
+	(95, 297000, (3840, 4400, 176, 88), (2160, 2250, 8, 10), 0x1E, 30, PictureAspectRatio::Ratio16_9, &[1]),
+	(96, 594000, (3840, 5280, 1056, 88), (2160, 2250, 8, 10), 0x1E, 50, PictureAspectRatio::Ratio16_9, &[1]),
+	(97, 594000, (3840, 4400, 176, 88), (2160, 2250, 8, 10), 0x1E, 60, PictureAspectRatio::Ratio16_9, &[1]),
+	(98, 297000, (4096, 5500, 1020, 88), (2160, 2250, 8, 10), 0x1E, 24, PictureAspectRatio::Ratio256_135, &[1]),
+	(99, 297000, (4096, 5280, 968, 88), (2160, 2250, 8, 10), 0x1E, 25, PictureAspectRatio::Ratio256_135, &[1]),
+	(100, 297000, (4096, 4400, 88, 88), (2160, 2250, 8, 10), 0x1E, 30, PictureAspectRatio::Ratio256_135, &[1]),
+	(101, 594000, (4096, 5280, 968, 88), (2160, 2250, 8, 10), 0x1E, 50, PictureAspectRatio::Ratio256_135, &[1]),
+	(102, 594000, (4096, 4400, 88, 88), (2160, 2250, 8, 10), 0x1E, 60, PictureAspectRatio::Ratio256_135, &[1]),
+	(103, 297000, (3840, 5500, 1276, 88), (2160, 2250, 8, 10), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(104, 297000, (3840, 5280, 1056, 88), (2160, 2250, 8, 10), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(105, 297000, (3840, 4400, 176, 88), (2160, 2250, 8, 10), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(106, 594000, (3840, 5280, 1056, 88), (2160, 2250, 8, 10), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(107, 594000, (3840, 4400, 176, 88), (2160, 2250, 8, 10), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(108, 90000, (1280, 2500, 960, 40), (720, 750, 5, 5), 0x1E, 48, PictureAspectRatio::Ratio16_9, &[1]),
+	(109, 90000, (1280, 2500, 960, 40), (720, 750, 5, 5), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(110, 99000, (1680, 2750, 810, 40), (720, 750, 5, 5), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(111, 148500, (1920, 2750, 638, 44), (1080, 1125, 4, 5), 0x1E, 48, PictureAspectRatio::Ratio16_9, &[1]),
+	(112, 148500, (1920, 2750, 638, 44), (1080, 1125, 4, 5), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(113, 198000, (2560, 3750, 998, 44), (1080, 1100, 4, 5), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(114, 594000, (3840, 5500, 1276, 88), (2160, 2250, 8, 10), 0x1E, 48, PictureAspectRatio::Ratio16_9, &[1]),
+	(115, 594000, (4096, 5500, 1020, 88), (2160, 2250, 8, 10), 0x1E, 48, PictureAspectRatio::Ratio256_135, &[1]),
+	(116, 594000, (3840, 5500, 1276, 88), (2160, 2250, 8, 10), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(117, 1188000, (3840, 5280, 1056, 88), (2160, 2250, 8, 10), 0x1E, 100, PictureAspectRatio::Ratio16_9, &[1]),
+	(118, 1188000, (3840, 4400, 176, 88), (2160, 2250, 8, 10), 0x1E, 120, PictureAspectRatio::Ratio16_9, &[1]),
+	(119, 1188000, (3840, 5280, 1056, 88), (2160, 2250, 8, 10), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(120, 1188000, (3840, 4400, 176, 88), (2160, 2250, 8, 10), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(121, 396000, (5120, 7500, 1996, 88), (2160, 2200, 8, 10), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(122, 396000, (5120, 7200, 1696, 88), (2160, 2200, 8, 10), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(123, 396000, (5120, 6000, 664, 88), (2160, 2200, 8, 10), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(124, 742500, (5120, 6250, 746, 88), (2160, 2475, 8, 10), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(125, 742500, (5120, 6600, 1096, 88), (2160, 2250, 8, 10), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(126, 742500, (5120, 5500, 164, 88), (2160, 2250, 8, 10), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(127, 1485000, (5120, 6600, 1096, 88), (2160, 2250, 8, 10), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(193, 1485000, (5120, 5500, 164, 88), (2160, 2250, 8, 10), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(194, 1188000, (7680, 11000, 2552, 176), (4320, 4500, 16, 20), 0x1E, 24, PictureAspectRatio::Ratio16_9, &[1]),
+	(195, 1188000, (7680, 10800, 2352, 176), (4320, 4400, 16, 20), 0x1E, 25, PictureAspectRatio::Ratio16_9, &[1]),
+	(196, 1188000, (7680, 9000, 552, 176), (4320, 4400, 16, 20), 0x1E, 30, PictureAspectRatio::Ratio16_9, &[1]),
+	(197, 2376000, (7680, 11000, 2552, 176), (4320, 4500, 16, 20), 0x1E, 48, PictureAspectRatio::Ratio16_9, &[1]),
+	(198, 2376000, (7680, 10800, 2352, 176), (4320, 4400, 16, 20), 0x1E, 50, PictureAspectRatio::Ratio16_9, &[1]),
+	(199, 2376000, (7680, 9000, 552, 176), (4320, 4400, 16, 20), 0x1E, 60, PictureAspectRatio::Ratio16_9, &[1]),
+	(200, 4752000, (7680, 10560, 2112, 176), (4320, 4500, 16, 20), 0x1E, 100, PictureAspectRatio::Ratio16_9, &[1]),
+	(201, 4752000, (7680, 8800, 352, 176), (4320, 4500, 16, 20), 0x1E, 120, PictureAspectRatio::Ratio16_9, &[1]),
+	(202, 1188000, (7680, 11000, 2552, 176), (4320, 4500, 16, 20), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(203, 1188000, (7680, 10800, 2352, 176), (4320, 4400, 16, 20), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(204, 1188000, (7680, 9000, 552, 176), (4320, 4400, 16, 20), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(205, 2376000, (7680, 11000, 2552, 176), (4320, 4500, 16, 20), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(206, 2376000, (7680, 10800, 2352, 176), (4320, 4400, 16, 20), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(207, 2376000, (7680, 9000, 552, 176), (4320, 4400, 16, 20), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(208, 4752000, (7680, 10560, 2112, 176), (4320, 4500, 16, 20), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(209, 4752000, (7680, 8800, 352, 176), (4320, 4500, 16, 20), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(210, 1485000, (10240, 12500, 1492, 176), (4320, 4950, 16, 20), 0x1E, 24, PictureAspectRatio::Ratio64_27, &[1]),
+	(211, 1485000, (10240, 13500, 2492, 176), (4320, 4400, 16, 20), 0x1E, 25, PictureAspectRatio::Ratio64_27, &[1]),
+	(212, 1485000, (10240, 11000, 288, 176), (4320, 4500, 16, 20), 0x1E, 30, PictureAspectRatio::Ratio64_27, &[1]),
+	(213, 2970000, (10240, 12500, 1492, 176), (4320, 4950, 16, 20), 0x1E, 48, PictureAspectRatio::Ratio64_27, &[1]),
+	(214, 2970000, (10240, 13500, 2492, 176), (4320, 4400, 16, 20), 0x1E, 50, PictureAspectRatio::Ratio64_27, &[1]),
+	(215, 2970000, (10240, 11000, 288, 176), (4320, 4500, 16, 20), 0x1E, 60, PictureAspectRatio::Ratio64_27, &[1]),
+	(216, 5940000, (10240, 13200, 2192, 176), (4320, 4500, 16, 20), 0x1E, 100, PictureAspectRatio::Ratio64_27, &[1]),
+	(217, 5940000, (10240, 11000, 288, 176), (4320, 4500, 16, 20), 0x1E, 120, PictureAspectRatio::Ratio64_27, &[1]),
+	(218, 1188000, (4096, 5280, 800, 88), (2160, 2250, 8, 10), 0x1E, 100, PictureAspectRatio::Ratio256_135, &[1]),
+	(219, 1188000, (4096, 4400, 88, 88), (2160, 2250, 8, 10), 0x1E, 120, PictureAspectRatio::Ratio256_135, &[1]),
+];
+
+/// Returns the video format of a VIC, or `None` if it is reserved.
+pub fn video_format(vic: u8) -> Option<VideoFormat> {
+    VIDEO_FORMATS.iter().find(|entry| entry.0 == vic).map(
+        |&(vic, pixel_clock, horizontal, vertical, features, refresh_rate, picture_aspect_ratio, pixel_repetition)| {
+            VideoFormat {
+                vic,
+                timing: mode_timing(pixel_clock, horizontal, vertical, features),
+                interlaced: features & 0x80 != 0,
+                refresh_rate,
+                picture_aspect_ratio,
+                pixel_repetition,
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_video_format() {
+		let format = video_format(97).unwrap();
+		assert_eq!(format.timing.horizontal_active_pixels, 3840);
+		assert_eq!(format.timing.vertical_active_lines, 2160);
+		assert_eq!(format.timing.pixel_clock, 594000);
+		assert_eq!(format.refresh_rate, 60);
+		assert_eq!(format.picture_aspect_ratio, PictureAspectRatio::Ratio16_9);
+
+		let format = video_format(5).unwrap();
+		assert!(format.interlaced);
+		assert_eq!(format.timing.vertical_active_lines, 540);
+
+		assert_eq!(video_format(0), None);
+		assert_eq!(video_format(128), None);
+		assert_eq!(video_format(220), None);
+	}
+
+	#[test]
+	fn test_video_formats_refresh_rate() {
+		for vic in (1..128).chain(193..220) {
+			let format = video_format(vic).unwrap();
+			let t = &format.timing;
+			let total = (t.horizontal_active_pixels + t.horizontal_blanking_pixels) as f64
+				* (t.vertical_active_lines + t.vertical_blanking_lines) as f64;
+			let refresh_rate = t.pixel_clock as f64 * 1000.0 / total;
+			assert!((refresh_rate / format.refresh_rate as f64 - 1.0).abs() < 0.01, "VIC {}", vic);
+		}
+	}
+}
@@ -0,0 +1,106 @@
+//! VESA Coordinated Video Timings (CVT 1.2) formula, for progressive modes without margins.
+
+use super::{mode_timing, DetailedTiming};
+
+const CELL_GRANULARITY: u16 = 8;
+/// Pixel clocks are multiples of 0.25 MHz.
+const CLOCK_STEP: f64 = 250.0;
+const MIN_V_FRONT_PORCH: u16 = 3;
+const MIN_V_BACK_PORCH: u16 = 6;
+
+// Standard blanking
+const MIN_VSYNC_BACK_PORCH: f64 = 550.0;
+const HSYNC_PERCENTAGE: u16 = 8;
+const C_PRIME: f64 = 30.0;
+const M_PRIME: f64 = 300.0;
+
+// Reduced blanking
+const RB_MIN_V_BLANK: f64 = 460.0;
+const RB_H_BLANK: u16 = 160;
+const RB_H_SYNC: u16 = 32;
+
+// The vertical sync width tells the aspect ratio of the mode
+fn vsync_width(width: u16, height: u16) -> u16 {
+    let (w, h) = (width as u32, height as u32);
+    if h % 3 == 0 && h * 4 / 3 == w {
+        4
+    } else if h % 9 == 0 && h * 16 / 9 == w {
+        5
+    } else if h % 10 == 0 && h * 16 / 10 == w {
+        6
+    } else if (h % 4 == 0 && h * 5 / 4 == w) || (h % 9 == 0 && h * 15 / 9 == w) {
+        7
+    } else {
+        10
+    }
+}
+
+/// Computes the timing of a mode with the CVT formula, with reduced blanking or not. The width is
+/// rounded down to a multiple of 8 pixels, the refresh rate is in Hz.
+pub fn cvt_timing(width: u16, height: u16, refresh_rate: f64, reduced_blanking: bool) -> DetailedTiming {
+    let h_active = width - width % CELL_GRANULARITY;
+    let v_active = height;
+    let vsync = vsync_width(h_active, v_active);
+    let frame_period = 1_000_000.0 / refresh_rate; // In µs
+
+    if reduced_blanking {
+        let h_period = (frame_period - RB_MIN_V_BLANK) / v_active as f64;
+        let v_blank = ((RB_MIN_V_BLANK / h_period) as u16 + 1).max(MIN_V_FRONT_PORCH + vsync + MIN_V_BACK_PORCH);
+        let h_total = h_active + RB_H_BLANK;
+        let v_total = v_active + v_blank;
+        let pixel_clock = refresh_rate * h_total as f64 * v_total as f64 / 1000.0;
+
+        // Positive horizontal and negative vertical sync
+        mode_timing(
+            ((pixel_clock / CLOCK_STEP).floor() * CLOCK_STEP) as u32,
+            (h_active, h_total, RB_H_BLANK / 2 - RB_H_SYNC, RB_H_SYNC),
+            (v_active, v_total, MIN_V_FRONT_PORCH, vsync),
+            0x1A,
+        )
+    } else {
+        let h_period = (frame_period - MIN_VSYNC_BACK_PORCH) / (v_active + MIN_V_FRONT_PORCH) as f64;
+        let vsync_back_porch = ((MIN_VSYNC_BACK_PORCH / h_period) as u16 + 1).max(vsync + MIN_V_BACK_PORCH);
+        let v_total = v_active + vsync_back_porch + MIN_V_FRONT_PORCH;
+
+        let duty_cycle = (C_PRIME - M_PRIME * h_period / 1000.0).max(20.0);
+        let h_blank = (h_active as f64 * duty_cycle / (100.0 - duty_cycle)) as u16;
+        let h_blank = h_blank - h_blank % (2 * CELL_GRANULARITY);
+        let h_total = h_active + h_blank;
+        let h_sync = (h_total as u32 * HSYNC_PERCENTAGE as u32 / 100) as u16;
+        let h_sync = h_sync - h_sync % CELL_GRANULARITY;
+        let pixel_clock = h_total as f64 * 1000.0 / h_period;
+
+        // Negative horizontal and positive vertical sync
+        mode_timing(
+            ((pixel_clock / CLOCK_STEP).floor() * CLOCK_STEP) as u32,
+            (h_active, h_total, h_blank / 2 - h_sync, h_sync),
+            (v_active, v_total, MIN_V_FRONT_PORCH, vsync),
+            0x1C,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_cvt_timing() {
+		// cvt 1920 1080 60
+		let timing = cvt_timing(1920, 1080, 60.0, false);
+		assert_eq!(timing.pixel_clock, 173_000);
+		assert_eq!((timing.horizontal_blanking_pixels, timing.horizontal_front_porch, timing.horizontal_sync_width), (656, 128, 200));
+		assert_eq!((timing.vertical_blanking_lines, timing.vertical_front_porch, timing.vertical_sync_width), (40, 3, 5));
+
+		// cvt -r 1920 1080 60
+		let timing = cvt_timing(1920, 1080, 60.0, true);
+		assert_eq!(timing.pixel_clock, 138_500);
+		assert_eq!((timing.horizontal_blanking_pixels, timing.horizontal_front_porch, timing.horizontal_sync_width), (160, 48, 32));
+		assert_eq!((timing.vertical_blanking_lines, timing.vertical_front_porch, timing.vertical_sync_width), (31, 3, 5));
+
+		// cvt 1280 1024 75, with the vertical sync width of 5:4
+		let timing = cvt_timing(1280, 1024, 75.0, false);
+		assert_eq!(timing.pixel_clock, 138_750);
+		assert_eq!(timing.vertical_sync_width, 7);
+	}
+}
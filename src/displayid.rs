@@ -0,0 +1,603 @@
+//! DisplayID 1.x and 2.x, either standalone or in EDID extension blocks.
+
+use nom::bytes::complete::take;
+use nom::number::complete::{be_u24, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+
+use super::{checksum, cta, Error};
+
+mod display;
+mod tile;
+mod timing;
+
+pub use self::display::{
+    AdaptiveSync, ColorCharacteristics, ColorDepths, ColorSpaceEotfs, ContentProtection, DisplayDevice,
+    DisplayFeatures, DisplayInterface, DisplayInterfaceFeatures, DisplayParameters, DisplayParametersV2,
+    InterfaceType, PowerSequencing, ProductIdentification, StereoDisplayInterface, TileBehavior, TileBezel,
+    TiledDisplayTopology,
+};
+pub use self::tile::{tiled_displays, Tile, TiledDisplay};
+pub use self::timing::{
+    AspectRatio, CvtFormula, DynamicRangeLimits, FormulaTiming, RangeLimits, ShortTiming, StereoSupport, Timing,
+    TimingCodeType, TimingCodes,
+};
+
+const SECTION_HEADER_SIZE: usize = 4;
+
+/// Product type in DisplayID 1.x, primary use case in DisplayID 2.x.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ProductType {
+	/// Extension section, which continues the product type of the base section.
+	Extension,
+	TestStructure,
+	DisplayPanel,
+	StandaloneDisplay,
+	TelevisionReceiver,
+	Repeater,
+	DirectDriveMonitor,
+	GenericDisplay,
+	DesktopProductivity,
+	DesktopGaming,
+	Presentation,
+	VirtualReality,
+	AugmentedReality,
+	Reserved(u8),
+}
+
+fn product_type(version: u8, v: u8) -> ProductType {
+    match (version, v) {
+        (_, 0) => ProductType::Extension,
+        (_, 1) => ProductType::TestStructure,
+        (1, 2) => ProductType::DisplayPanel,
+        (1, 3) => ProductType::StandaloneDisplay,
+        (1, 4) => ProductType::TelevisionReceiver,
+        (1, 5) => ProductType::Repeater,
+        (1, 6) => ProductType::DirectDriveMonitor,
+        (1, v) => ProductType::Reserved(v),
+        (_, 2) => ProductType::GenericDisplay,
+        (_, 3) => ProductType::TelevisionReceiver,
+        (_, 4) => ProductType::DesktopProductivity,
+        (_, 5) => ProductType::DesktopGaming,
+        (_, 6) => ProductType::Presentation,
+        (_, 7) => ProductType::VirtualReality,
+        (_, 8) => ProductType::AugmentedReality,
+        (_, v) => ProductType::Reserved(v),
+    }
+}
+
+fn encode_product_type(product_type: ProductType, version: u8) -> u8 {
+    match product_type {
+        ProductType::Extension => 0,
+        ProductType::TestStructure => 1,
+        ProductType::DisplayPanel | ProductType::GenericDisplay => 2,
+        ProductType::StandaloneDisplay => 3,
+        ProductType::TelevisionReceiver if version == 1 => 4,
+        ProductType::TelevisionReceiver => 3,
+        ProductType::Repeater | ProductType::DesktopProductivity => 4,
+        ProductType::DirectDriveMonitor | ProductType::DesktopGaming => 5,
+        ProductType::Presentation => 6,
+        ProductType::VirtualReality => 7,
+        ProductType::AugmentedReality => 8,
+        ProductType::Reserved(v) => v,
+    }
+}
+
+/// Data blocks of DisplayID 1.x and 2.x, the blocks shared by both versions have the same variants.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DataBlock {
+	ProductIdentification(ProductIdentification),
+	DisplayParameters(DisplayParameters),
+	ColorCharacteristics(ColorCharacteristics),
+	/// Type I timings, with the revision byte of their block.
+	TypeITimings(u8, Vec<Timing>),
+	/// Type II timings, with the revision byte of their block.
+	TypeIITimings(u8, Vec<Timing>),
+	TypeIIITimings(Vec<ShortTiming>),
+	TypeIVTimings(TimingCodes),
+	/// VESA DMT IDs of the supported timings.
+	VesaTimings(Vec<u8>),
+	/// CTA-861 VICs of the supported timings.
+	CtaTimings(Vec<u8>),
+	RangeLimits(RangeLimits),
+	SerialNumber(String),
+	AsciiString(String),
+	DisplayDevice(DisplayDevice),
+	PowerSequencing(PowerSequencing),
+	/// Transfer characteristics, whose sample encoding depends on the block revision flags.
+	TransferCharacteristics(u8, Vec<u8>),
+	DisplayInterface(DisplayInterface),
+	StereoDisplayInterface(StereoDisplayInterface),
+	/// Tiled display topology, with the revision byte of its block.
+	TiledDisplayTopology(u8, TiledDisplayTopology),
+	DisplayParametersV2(DisplayParametersV2),
+	/// Type VII timings, with the revision byte of their block.
+	TypeVIITimings(u8, Vec<Timing>),
+	TypeVIIITimings(TimingCodes),
+	TypeIXTimings(Vec<FormulaTiming>),
+	/// Type X timings, with the revision flags of their block.
+	TypeXTimings(u8, Vec<FormulaTiming>),
+	DynamicRangeLimits(DynamicRangeLimits),
+	DisplayInterfaceFeatures(DisplayInterfaceFeatures),
+	/// Unique identifier of the product, shared by its multiple output devices.
+	ContainerId([u8; 16]),
+	/// Adaptive-Sync descriptors, with the revision flags of their block.
+	AdaptiveSync(u8, Vec<AdaptiveSync>),
+	/// CTA-861 data blocks, carried by DisplayID 2.x.
+	Cta(Vec<cta::DataBlock>),
+	VendorSpecific { oui: u32, payload: Vec<u8> },
+	/// Data block with an unknown tag, with its revision byte.
+	Unknown(u8, u8, Vec<u8>),
+}
+
+fn parse_data_block(input: &[u8]) -> IResult<&[u8], DataBlock> {
+    let (input, (tag, revision, length)) = tuple((le_u8, le_u8, le_u8))(input)?;
+    let (input, payload) = take(length)(input)?;
+
+    // A malformed payload doesn't spoil the rest of the section, the block is kept as is
+    match decode_data_block(tag, revision, payload) {
+        Ok((_, block)) => Ok((input, block)),
+        Err(_) => Ok((input, DataBlock::Unknown(tag, revision, payload.to_vec()))),
+    }
+}
+
+fn decode_data_block(tag: u8, revision: u8, payload: &[u8]) -> IResult<&[u8], DataBlock> {
+    let block = match tag {
+        0x00 => DataBlock::ProductIdentification(display::parse_product_identification(payload)?.1),
+        0x01 => DataBlock::DisplayParameters(display::parse_display_parameters(payload)?.1),
+        0x02 => DataBlock::ColorCharacteristics(display::parse_color_characteristics(payload)?.1),
+        0x03 => DataBlock::TypeITimings(revision, timing::parse_timings(payload, timing::parse_type_1_timing)?.1),
+        0x04 => DataBlock::TypeIITimings(revision, timing::parse_timings(payload, timing::parse_type_2_timing)?.1),
+        0x05 => DataBlock::TypeIIITimings(timing::parse_type_3_timings(payload)?.1),
+        0x06 => DataBlock::TypeIVTimings(timing::parse_timing_codes(payload, revision, false)?.1),
+        0x07 => DataBlock::VesaTimings(timing::bitmap_codes(payload)),
+        0x08 => DataBlock::CtaTimings(timing::bitmap_codes(payload)),
+        0x09 => DataBlock::RangeLimits(timing::parse_range_limits(payload)?.1),
+        0x0A => DataBlock::SerialNumber(display::ascii(payload)),
+        0x0B => DataBlock::AsciiString(display::ascii(payload)),
+        0x0C => DataBlock::DisplayDevice(display::parse_display_device(payload)?.1),
+        0x0D => DataBlock::PowerSequencing(display::parse_power_sequencing(payload)?.1),
+        0x0E => DataBlock::TransferCharacteristics(revision, payload.to_vec()),
+        0x0F => DataBlock::DisplayInterface(display::parse_display_interface(payload)?.1),
+        0x10 => DataBlock::StereoDisplayInterface(display::stereo_display_interface(revision, payload)?.1),
+        0x12 => DataBlock::TiledDisplayTopology(revision, display::parse_tiled_display_topology(payload)?.1),
+        0x20 => DataBlock::ProductIdentification(display::parse_product_identification(payload)?.1),
+        0x21 => DataBlock::DisplayParametersV2(display::parse_display_parameters_v2(payload, revision)?.1),
+        0x22 => DataBlock::TypeVIITimings(revision, timing::parse_timings(payload, timing::parse_type_7_timing)?.1),
+        // Codes are two bytes long when the revision flags say so
+        0x23 => DataBlock::TypeVIIITimings(timing::parse_timing_codes(payload, revision, revision & 0x08 != 0)?.1),
+        0x24 => DataBlock::TypeIXTimings(timing::parse_type_9_timings(payload)?.1),
+        0x25 => DataBlock::DynamicRangeLimits(timing::parse_dynamic_range_limits(payload, revision)?.1),
+        0x26 => DataBlock::DisplayInterfaceFeatures(display::parse_display_interface_features(payload)?.1),
+        0x27 => DataBlock::StereoDisplayInterface(display::stereo_display_interface(revision, payload)?.1),
+        0x28 => DataBlock::TiledDisplayTopology(revision, display::parse_tiled_display_topology(payload)?.1),
+        0x29 => {
+            let (_, id) = take(16usize)(payload)?;
+            let mut container_id = [0; 16];
+            container_id.copy_from_slice(id);
+            DataBlock::ContainerId(container_id)
+        }
+        // Descriptors longer than the ones decoded are kept raw as unknown blocks
+        0x2A if (revision >> 4) & 0x7 <= 1 => {
+            DataBlock::TypeXTimings(revision, timing::parse_type_10_timings(payload, revision)?.1)
+        }
+        0x2B if (revision >> 4) & 0x7 == 0 => {
+            DataBlock::AdaptiveSync(revision, display::parse_adaptive_sync(payload)?.1)
+        }
+        0x7E | 0x7F => {
+            // Unlike in CTA-861, the OUI is stored most significant byte first
+            let (payload, oui) = be_u24(payload)?;
+            DataBlock::VendorSpecific { oui, payload: payload.to_vec() }
+        }
+        0x81 => DataBlock::Cta(cta::parse_data_block_collection(payload)?.1),
+        _ => DataBlock::Unknown(tag, revision, payload.to_vec()),
+    };
+    Ok((&payload[payload.len()..], block))
+}
+
+// Returns the tag, the revision and the payload of a data block, the tags of the blocks shared by
+// both versions depending on the version of the section
+fn encode_data_block_payload(block: &DataBlock, version: u8) -> Option<(u8, u8, Vec<u8>)> {
+    let v2 = version >= 2;
+    let block = match *block {
+        DataBlock::ProductIdentification(ref product) => {
+            (if v2 { 0x20 } else { 0x00 }, 0, display::encode_product_identification(product))
+        }
+        DataBlock::DisplayParameters(ref parameters) => (0x01, 0, display::encode_display_parameters(parameters)),
+        DataBlock::ColorCharacteristics(ref characteristics) => {
+            (0x02, 0, display::encode_color_characteristics(characteristics))
+        }
+        DataBlock::TypeITimings(revision, ref timings) => {
+            (0x03, revision, timing::encode_timings(timings, timing::encode_type_1_timing))
+        }
+        DataBlock::TypeIITimings(revision, ref timings) => {
+            (0x04, revision, timing::encode_timings(timings, timing::encode_type_2_timing))
+        }
+        DataBlock::TypeIIITimings(ref timings) => (0x05, 0, timing::encode_type_3_timings(timings)),
+        DataBlock::TypeIVTimings(ref codes) => {
+            let (revision, payload) = timing::encode_timing_codes(codes);
+            // Only Type VIII blocks can have two-byte codes
+            if revision & 0x08 != 0 {
+                return None;
+            }
+            (0x06, revision, payload)
+        }
+        DataBlock::VesaTimings(ref codes) => (0x07, 0, timing::encode_bitmap_codes(codes, 10)),
+        DataBlock::CtaTimings(ref codes) => (0x08, 0, timing::encode_bitmap_codes(codes, 8)),
+        DataBlock::RangeLimits(ref limits) => (0x09, 0, timing::encode_range_limits(limits)),
+        DataBlock::SerialNumber(ref serial) => (0x0A, 0, display::encode_ascii(serial)),
+        DataBlock::AsciiString(ref string) => (0x0B, 0, display::encode_ascii(string)),
+        DataBlock::DisplayDevice(ref device) => (0x0C, 0, display::encode_display_device(device)),
+        DataBlock::PowerSequencing(ref sequencing) => (0x0D, 0, display::encode_power_sequencing(sequencing)),
+        DataBlock::TransferCharacteristics(revision, ref data) => (0x0E, revision, data.clone()),
+        DataBlock::DisplayInterface(ref interface) => (0x0F, 0, display::encode_display_interface(interface)),
+        DataBlock::StereoDisplayInterface(ref interface) => {
+            let (revision, payload) = display::encode_stereo_display_interface(interface);
+            (if v2 { 0x27 } else { 0x10 }, revision, payload)
+        }
+        DataBlock::TiledDisplayTopology(revision, ref topology) => {
+            (if v2 { 0x28 } else { 0x12 }, revision, display::encode_tiled_display_topology(topology))
+        }
+        DataBlock::DisplayParametersV2(ref parameters) => {
+            let (revision, payload) = display::encode_display_parameters_v2(parameters);
+            (0x21, revision, payload)
+        }
+        DataBlock::TypeVIITimings(revision, ref timings) => {
+            (0x22, revision, timing::encode_timings(timings, timing::encode_type_7_timing))
+        }
+        DataBlock::TypeVIIITimings(ref codes) => {
+            let (revision, payload) = timing::encode_timing_codes(codes);
+            (0x23, revision, payload)
+        }
+        DataBlock::TypeIXTimings(ref timings) => (0x24, 0, timing::encode_type_9_timings(timings)),
+        DataBlock::DynamicRangeLimits(ref limits) => {
+            let (revision, payload) = timing::encode_dynamic_range_limits(limits);
+            (0x25, revision, payload)
+        }
+        DataBlock::DisplayInterfaceFeatures(ref features) => {
+            (0x26, 0, display::encode_display_interface_features(features))
+        }
+        DataBlock::ContainerId(ref id) => (0x29, 0, id.to_vec()),
+        DataBlock::TypeXTimings(flags, ref timings) => {
+            let (revision, payload) = timing::encode_type_10_timings(timings, flags);
+            (0x2A, revision, payload)
+        }
+        // Descriptors are always 6 bytes long
+        DataBlock::AdaptiveSync(flags, ref descriptors) => {
+            (0x2B, flags & !0x70, display::encode_adaptive_sync(descriptors))
+        }
+        DataBlock::Cta(ref blocks) => (0x81, 0, cta::encode_data_block_collection(blocks)?),
+        DataBlock::VendorSpecific { oui, ref payload } => {
+            let mut data = oui.to_be_bytes()[1..].to_vec();
+            data.extend_from_slice(payload);
+            (if v2 { 0x7E } else { 0x7F }, 0, data)
+        }
+        DataBlock::Unknown(tag, revision, ref data) => (tag, revision, data.clone()),
+    };
+    Some(block)
+}
+
+/// Encodes a data block with its header, returns `None` if its payload is too long for it.
+fn encode_data_block(block: &DataBlock, version: u8, out: &mut Vec<u8>) -> Option<()> {
+    let (tag, revision, payload) = encode_data_block_payload(block, version)?;
+    if payload.len() > 0xFF {
+        return None;
+    }
+    out.extend_from_slice(&[tag, revision, payload.len() as u8]);
+    out.extend(payload);
+    Some(())
+}
+
+// Data blocks are followed by zero padding up to the end of the section, which is left
+fn parse_data_blocks(input: &[u8]) -> IResult<&[u8], Vec<DataBlock>> {
+    let mut input = input;
+    let mut data_blocks = Vec::new();
+    while input.iter().any(|&b| b != 0) {
+        let (rest, block) = parse_data_block(input)?;
+        data_blocks.push(block);
+        input = rest;
+    }
+    Ok((input, data_blocks))
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DisplayId {
+	pub version: u8,
+	pub revision: u8,
+	pub product_type: ProductType,
+	/// Number of extension sections following the base section.
+	pub extension_count: u8,
+	/// Data blocks of the section, and of its extension sections when parsed standalone.
+	pub data_blocks: Vec<DataBlock>,
+	/// Zero bytes following the data blocks in the section, `None` to pad it up to the end of the
+	/// extension block when encoding.
+	pub padding: Option<u8>,
+}
+
+fn section_length(section: &[u8]) -> usize {
+    SECTION_HEADER_SIZE + section[1] as usize + 1
+}
+
+// Returns the expected and actual checksums of a complete section
+fn section_checksum(section: &[u8]) -> (u8, u8) {
+    let (data, actual) = section.split_at(section.len() - 1);
+    (checksum(data), actual[0])
+}
+
+fn supported_version(version: u8) -> bool {
+    version == 1 || version == 2
+}
+
+// Parses a complete section, whose checksum has already been verified
+fn parse_section(section: &[u8]) -> IResult<&[u8], DisplayId> {
+    let (input, (version, _length, product, extension_count)) = tuple((le_u8, le_u8, le_u8, le_u8))(section)?;
+    let (input, payload) = take(section.len() - SECTION_HEADER_SIZE - 1)(input)?;
+    let (padding, data_blocks) = parse_data_blocks(payload)?;
+    Ok((
+        &input[input.len()..],
+        DisplayId {
+            version: version >> 4,
+            revision: version & 0xF,
+            product_type: product_type(version >> 4, product),
+            extension_count,
+            data_blocks,
+            padding: Some(padding.len() as u8),
+        },
+    ))
+}
+
+/// Parses a 128-byte DisplayID extension block, whose checksum has already been verified.
+pub(crate) fn parse_displayid_extension(block: &[u8]) -> IResult<&[u8], DisplayId> {
+    let section = &block[1..block.len() - 1];
+    let length = section_length(section);
+    let invalid = length > section.len() || !supported_version(section[0] >> 4) || {
+        let (expected, actual) = section_checksum(&section[..length]);
+        expected != actual
+    };
+    if invalid {
+        return Err(nom::Err::Error(nom::error::Error::new(block, nom::error::ErrorKind::Verify)));
+    }
+    let (_, displayid) = parse_section(&section[..length])?;
+    Ok((&block[block.len()..], displayid))
+}
+
+/// Encodes a 128-byte DisplayID extension block, without its checksum. The section keeps its
+/// padding, or is padded up to the end of the block without one. Returns `None` if its contents
+/// don't fit in a block.
+pub(crate) fn encode_displayid_extension(displayid: &DisplayId) -> Option<Vec<u8>> {
+    let mut data_blocks = Vec::new();
+    for block in &displayid.data_blocks {
+        encode_data_block(block, displayid.version, &mut data_blocks)?;
+    }
+    // The block tag, the section header and checksum, and the block checksum
+    let max_length = 128 - SECTION_HEADER_SIZE - 3;
+    let length = match displayid.padding {
+        Some(padding) => data_blocks.len() + padding as usize,
+        None => max_length,
+    };
+    if data_blocks.len() > length || length > max_length {
+        return None;
+    }
+    data_blocks.resize(length, 0);
+
+    let mut section = vec![
+        displayid.version << 4 | (displayid.revision & 0xF),
+        length as u8,
+        encode_product_type(displayid.product_type, displayid.version),
+        displayid.extension_count,
+    ];
+    section.extend(data_blocks);
+    section.push(checksum(&section));
+
+    let mut block = vec![0x70];
+    block.extend(section);
+    block.resize(128, 0);
+    Some(block)
+}
+
+/// Recomputes the section checksum of a 128-byte DisplayID extension block, unless its length
+/// exceeds the block. The block checksum is left to the caller.
+pub(crate) fn fix_extension_checksum(block: &mut [u8]) {
+    let section = &mut block[1..];
+    let length = section_length(section);
+    if length < section.len() {
+        let (expected, _) = section_checksum(&section[..length]);
+        section[length - 1] = expected;
+    }
+}
+
+// Parses the section at this offset of a standalone structure, and returns it with its length
+fn parse_standalone_section(data: &[u8], offset: usize, section: usize) -> Result<(DisplayId, usize), Error> {
+    let expected = offset + SECTION_HEADER_SIZE + 1;
+    if data.len() < expected {
+        return Err(Error::Truncated { expected, actual: data.len() });
+    }
+    let expected = offset + section_length(&data[offset..]);
+    if data.len() < expected {
+        return Err(Error::Truncated { expected, actual: data.len() });
+    }
+    let bytes = &data[offset..expected];
+
+    let (expected, actual) = section_checksum(bytes);
+    if expected != actual {
+        return Err(Error::ChecksumMismatch { block: section, expected, actual });
+    }
+    let (version, revision) = (bytes[0] >> 4, bytes[0] & 0xF);
+    if !supported_version(version) {
+        return Err(Error::UnsupportedVersion { version, revision });
+    }
+    let (_, displayid) = parse_section(bytes).map_err(|_| Error::MalformedSection { section })?;
+    Ok((displayid, bytes.len()))
+}
+
+/// Parses a standalone DisplayID 1.x or 2.x structure, made of a base section followed by its extension
+/// sections. Errors refer to sections by index, 0 being the base section.
+pub fn parse(data: &[u8]) -> Result<DisplayId, Error> {
+    let (mut displayid, mut offset) = parse_standalone_section(data, 0, 0)?;
+    for section in 1..=displayid.extension_count as usize {
+        let (extension, length) = parse_standalone_section(data, offset, section)?;
+        displayid.data_blocks.extend(extension.data_blocks);
+        offset += length;
+    }
+    Ok(displayid)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn section(version: u8, product_type: u8, extension_count: u8, data_blocks: &[u8]) -> Vec<u8> {
+		let mut section = vec![version, data_blocks.len() as u8, product_type, extension_count];
+		section.extend_from_slice(data_blocks);
+		section.push(checksum(&section));
+		section
+	}
+
+	const DATA_BLOCKS: [u8; 27] = [
+		0x03, 0x00, 0x14, // Type I timing
+		0x01, 0x3A, 0x00, 0x84, 0x7F, 0x07, 0x17, 0x01, 0x57, 0x80, 0x2B, 0x00, 0x37, 0x04, 0x2C, 0x00, 0x03,
+		0x80, 0x04, 0x00,
+		0x0A, 0x00, 0x01, 0x41, // Serial number
+	];
+
+	#[test]
+	fn test_displayid_extension() {
+		let mut block = vec![0x70];
+		block.extend(section(0x12, 0x02, 0, &DATA_BLOCKS));
+		block.resize(127, 0);
+		block.push(checksum(&block));
+
+		let displayid = parse_displayid_extension(&block).unwrap().1;
+		assert_eq!((displayid.version, displayid.revision), (1, 2));
+		assert_eq!(displayid.product_type, ProductType::DisplayPanel);
+		assert_eq!(displayid.data_blocks.len(), 2);
+		match displayid.data_blocks[0] {
+			DataBlock::TypeITimings(0, ref timings) => assert_eq!(timings[0].timing.pixel_clock, 148_500),
+			ref block => panic!("unexpected {:?}", block),
+		}
+		assert_eq!(displayid.data_blocks[1], DataBlock::SerialNumber("A".to_string()));
+
+		// The section checksum is wrong
+		block[5] ^= 1;
+		block[127] ^= 1;
+		assert!(parse_displayid_extension(&block).is_err());
+	}
+
+	#[test]
+	fn test_displayid_standalone() {
+		let mut data = section(0x13, 0x03, 1, &DATA_BLOCKS[..23]);
+		data.extend(section(0x13, 0x00, 0, &[0x7F, 0x00, 0x04, 0x00, 0x1B, 0x21, 0x55]));
+
+		let displayid = parse(&data).unwrap();
+		assert_eq!(displayid.product_type, ProductType::StandaloneDisplay);
+		assert_eq!(displayid.data_blocks.len(), 2);
+		assert_eq!(displayid.data_blocks[1], DataBlock::VendorSpecific { oui: 0x001B21, payload: vec![0x55] });
+
+		assert_eq!(parse(&data[..35]), Err(Error::Truncated { expected: 40, actual: 35 }));
+		let mut bad = data.clone();
+		bad[30] = 0x14;
+		assert_eq!(parse(&bad), Err(Error::ChecksumMismatch { block: 1, expected: 0xBE, actual: 0xD2 }));
+		assert_eq!(parse(&section(0x30, 0, 0, &[])), Err(Error::UnsupportedVersion { version: 3, revision: 0 }));
+		// A data block longer than the section
+		assert_eq!(parse(&section(0x13, 0, 0, &[0x0B, 0x00, 0x02, 0x41])), Err(Error::MalformedSection { section: 0 }));
+		// A malformed data block is kept as is
+		let displayid = parse(&section(0x13, 0, 0, &[0x00, 0x00, 0x01, 0x41])).unwrap();
+		assert_eq!(displayid.data_blocks, vec![DataBlock::Unknown(0x00, 0x00, vec![0x41])]);
+	}
+
+	#[test]
+	fn test_encode_displayid_extension() {
+		let mut blocks = DATA_BLOCKS.to_vec();
+		blocks.extend_from_slice(&[
+			0x12, 0x00, 0x16, 0xC1, 0x10, 0x10, 0x00, 0xFF, 0x0E, 0x6F, 0x08, 0x0A, 0x00, 0x00, 0x14, 0x14, 0x44,
+			0x45, 0x4C, 0x34, 0x12, 0x01, 0x00, 0x00, 0x00, // Tiled display topology
+			0x7F, 0x00, 0x04, 0x00, 0x1B, 0x21, 0x55, // Vendor-specific
+		]);
+		blocks.resize(121, 0);
+		let mut block = vec![0x70];
+		block.extend(section(0x12, 0x02, 0, &blocks));
+		block.push(0);
+
+		let displayid = parse_displayid_extension(&block).unwrap().1;
+		assert_eq!(encode_displayid_extension(&displayid).unwrap(), block);
+
+		let data = section(0x20, 0x05, 0, &[
+			0x21, 0x00, 0x1D, 0x8C, 0x0A, 0xF4, 0x05, 0xFF, 0x0E, 0x6F, 0x08, 0x00, 0xB8, 0x52, 0xA5, 0xCD, 0xCC, 0xCC,
+			0x66, 0x06, 0x0F, 0x0C, 0x02, 0x54, 0x00, 0x64, 0x00, 0x66, 0x00, 0x00, 0x13, 0x78, // Display parameters
+			0x2A, 0x10, 0x0E, 0x03, 0xFF, 0x09, 0x9F, 0x05, 0x67, 0x01, 0x00, 0xFF, 0x0E, 0x6F, 0x08, 0x3B, 0x00, // Type X
+			0x2B, 0x00, 0x06, 0x01, 0x10, 0x30, 0x8F, 0x00, 0x08, // Adaptive-Sync
+		]);
+		let mut displayid = parse(&data).unwrap();
+		assert_eq!(displayid.padding, Some(0));
+		let block = encode_displayid_extension(&displayid).unwrap();
+		assert_eq!(block[1..=data.len()], data[..]);
+
+		// Without padding, the section is padded up to the end of the block
+		displayid.padding = None;
+		let block = encode_displayid_extension(&displayid).unwrap();
+		assert_eq!((block[1], block[2]), (data[0], 121));
+		assert_eq!(block[3..data.len()], data[2..data.len() - 1]);
+		assert_eq!(parse_displayid_extension(&block).unwrap().1.data_blocks, displayid.data_blocks);
+	}
+
+	#[test]
+	fn test_displayid_round_trip() {
+		let mut type_1 = DATA_BLOCKS[..23].to_vec();
+		type_1[1] = 0x01;
+		let mut type_7 = DATA_BLOCKS[..23].to_vec();
+		type_7[..2].copy_from_slice(&[0x22, 0x08]);
+		let mut v2 = type_7;
+		v2.extend_from_slice(&[
+			0x23, 0x08, 0x04, 0x10, 0x00, 0x61, 0x00, // Type VIII timing codes
+			0x28, 0x01, 0x16, 0xC1, 0x10, 0x10, 0x00, 0xFF, 0x0E, 0x6F, 0x08, 0x0A, 0x00, 0x00, 0x14, 0x14, 0x44,
+			0x45, 0x4C, 0x34, 0x12, 0x01, 0x00, 0x00, 0x00, // Tiled display topology
+			0x2A, 0x10, 0x07, 0x03, 0xFF, 0x09, 0x9F, 0x05, 0x3B, 0x00, // Type X
+			0x2B, 0x10, 0x0E, 0x01, 0x10, 0x30, 0x8F, 0x00, 0x08, 0x55, 0x01, 0x10, 0x30, 0x2B, 0x01, 0x08,
+			0x55, // Adaptive-Sync with 7-byte descriptors
+		]);
+
+		for (version, data_blocks) in [(0x12, vec![]), (0x13, type_1), (0x20, v2)] {
+			let mut block = vec![0x70];
+			block.extend(section(version, 0x00, 0, &data_blocks));
+			block.resize(128, 0);
+
+			let displayid = parse_displayid_extension(&block).unwrap().1;
+			assert_eq!(encode_displayid_extension(&displayid).unwrap(), block);
+			if version == 0x20 {
+				// Its descriptors are longer than the ones decoded
+				assert!(matches!(displayid.data_blocks[4], DataBlock::Unknown(0x2B, 0x10, _)));
+			}
+		}
+	}
+
+	#[test]
+	fn test_displayid_2() {
+		let data = section(0x20, 0x05, 0, &[
+			0x23, 0x08, 0x04, 0x10, 0x00, 0x61, 0x00, // Type VIII timing codes
+			0x29, 0x00, 0x10, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+			0x0E, 0x0F, // ContainerID
+			0x81, 0x00, 0x03, 0xE2, 0x00, 0x0F, // CTA colorimetry
+		]);
+
+		let displayid = parse(&data).unwrap();
+		assert_eq!((displayid.version, displayid.revision), (2, 0));
+		assert_eq!(displayid.product_type, ProductType::DesktopGaming);
+		assert_eq!(displayid.data_blocks.len(), 3);
+		assert_eq!(
+			displayid.data_blocks[0],
+			DataBlock::TypeVIIITimings(TimingCodes {
+				kind: TimingCodeType::Dmt,
+				codes: vec![0x10, 0x61],
+				two_byte_codes: true,
+			})
+		);
+		match displayid.data_blocks[1] {
+			DataBlock::ContainerId(ref id) => assert_eq!(id[15], 0x0F),
+			ref block => panic!("unexpected {:?}", block),
+		}
+		match displayid.data_blocks[2] {
+			DataBlock::Cta(ref blocks) => assert_eq!(blocks.len(), 1),
+			ref block => panic!("unexpected {:?}", block),
+		}
+	}
+}
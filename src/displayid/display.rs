@@ -0,0 +1,913 @@
+//! Product and display description data blocks of DisplayID 1.x and 2.x.
+
+use nom::bytes::complete::take;
+use nom::combinator::{all_consuming, map};
+use nom::multi::{count, many0};
+use nom::number::complete::{le_u16, le_u32, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+
+use super::super::{ChromaticityPoint, ManufactureDate};
+
+pub(crate) fn ascii(input: &[u8]) -> String {
+    input.iter().map(|&b| b as char).collect::<String>().trim().to_string()
+}
+
+// Inverse of `ascii`, characters outside of Latin-1 are replaced
+pub(crate) fn encode_ascii(s: &str) -> Vec<u8> {
+    s.chars().map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' }).collect()
+}
+
+// Week 0xFF stands for a model year, years start at 2000
+fn manufacture_date(week: u8, year: u8) -> ManufactureDate {
+    let year = year as u16 + 2000;
+    match week {
+        0 => ManufactureDate::Manufactured { week: None, year },
+        0xFF => ManufactureDate::ModelYear(year),
+        week => ManufactureDate::Manufactured { week: Some(week), year },
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProductIdentification {
+	/// Manufacturer's PNP ID in ASCII, or IEEE OUI.
+	pub vendor_id: [u8; 3],
+	pub product_code: u16,
+	pub serial: u32,
+	pub date: ManufactureDate,
+	pub name: String,
+}
+
+pub(crate) fn parse_product_identification(input: &[u8]) -> IResult<&[u8], ProductIdentification> {
+    let (input, (vendor_id, product_code, serial, week, year, name_length)) =
+        tuple((take(3usize), le_u16, le_u32, le_u8, le_u8, le_u8))(input)?;
+    let (input, name) = take(name_length)(input)?;
+    Ok((
+        input,
+        ProductIdentification {
+            vendor_id: [vendor_id[0], vendor_id[1], vendor_id[2]],
+            product_code,
+            serial,
+            date: manufacture_date(week, year),
+            name: ascii(name),
+        },
+    ))
+}
+
+pub(crate) fn encode_product_identification(product: &ProductIdentification) -> Vec<u8> {
+    let (week, year) = match product.date {
+        ManufactureDate::Manufactured { week, year } => (week.unwrap_or(0), year),
+        ManufactureDate::ModelYear(year) => (0xFF, year),
+    };
+    let name = encode_ascii(&product.name);
+    let mut out = product.vendor_id.to_vec();
+    out.extend_from_slice(&product.product_code.to_le_bytes());
+    out.extend_from_slice(&product.serial.to_le_bytes());
+    out.extend_from_slice(&[week, year.saturating_sub(2000) as u8, name.len() as u8]);
+    out.extend(name);
+    out
+}
+
+// Gammas are stored as 100 times their value minus one, 0xFF standing for an unspecified gamma
+fn encode_gamma(gamma: Option<f32>) -> u8 {
+    gamma.map_or(0xFF, |g| (g * 100.0 - 100.0).round().clamp(0.0, 254.0) as u8)
+}
+
+fn scale_u16(v: f32, scale: f32) -> u16 {
+    (v * scale).round().clamp(0.0, 65535.0) as u16
+}
+
+fn scale_u8(v: f32, scale: f32) -> u8 {
+    (v * scale).round().clamp(0.0, 255.0) as u8
+}
+
+bitflags! {
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct DisplayFeatures: u8 {
+		const DEINTERLACING = 1 << 0;
+		/// Audio Content Protection and ISRC packets are supported.
+		const ACP_ISRC = 1 << 1;
+		const FIXED_PIXEL_FORMAT = 1 << 2;
+		const FIXED_TIMING = 1 << 3;
+		/// VESA DPM power management.
+		const POWER_MANAGEMENT = 1 << 4;
+		/// The audio inputs override the audio of the video interface by default.
+		const AUDIO_INPUT_OVERRIDE = 1 << 5;
+		const SEPARATE_AUDIO_INPUTS = 1 << 6;
+		/// Audio is supported on the video interface.
+		const AUDIO = 1 << 7;
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DisplayParameters {
+	/// Image size in millimeters.
+	pub width: f32,
+	pub height: f32,
+	pub horizontal_pixels: u16,
+	pub vertical_pixels: u16,
+	pub features: DisplayFeatures,
+	pub gamma: Option<f32>,
+	pub aspect_ratio: f32,
+	/// Bits per color component of the interface.
+	pub bits_per_color_overall: u8,
+	/// Bits per color component of the display.
+	pub bits_per_color_native: u8,
+}
+
+pub(crate) fn parse_display_parameters(input: &[u8]) -> IResult<&[u8], DisplayParameters> {
+    map(
+        tuple((le_u16, le_u16, le_u16, le_u16, le_u8, le_u8, le_u8, le_u8)),
+        |(width, height, horizontal_pixels, vertical_pixels, features, gamma, aspect_ratio, depth)| {
+            DisplayParameters {
+                width: width as f32 / 10.0,
+                height: height as f32 / 10.0,
+                horizontal_pixels,
+                vertical_pixels,
+                features: DisplayFeatures::from_bits_truncate(features),
+                gamma: if gamma != 0xFF { Some((gamma as f32 + 100.0) / 100.0) } else { None },
+                aspect_ratio: (aspect_ratio as f32 + 100.0) / 100.0,
+                bits_per_color_overall: (depth >> 4) + 1,
+                bits_per_color_native: (depth & 0xF) + 1,
+            }
+        },
+    )(input)
+}
+
+pub(crate) fn encode_display_parameters(parameters: &DisplayParameters) -> Vec<u8> {
+    let mut out = Vec::new();
+    for v in &[
+        scale_u16(parameters.width, 10.0),
+        scale_u16(parameters.height, 10.0),
+        parameters.horizontal_pixels,
+        parameters.vertical_pixels,
+    ] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+    out.extend_from_slice(&[
+        parameters.features.bits(),
+        encode_gamma(parameters.gamma),
+        scale_u8(parameters.aspect_ratio - 1.0, 100.0),
+        (parameters.bits_per_color_overall.saturating_sub(1) & 0xF) << 4
+            | (parameters.bits_per_color_native.saturating_sub(1) & 0xF),
+    ]);
+    out
+}
+
+// IEEE 754 half-precision float
+fn half_float(v: u16) -> f32 {
+    let mantissa = (v & 0x3FF) as f32;
+    let value = match (v >> 10) & 0x1F {
+        0 => mantissa / 1024.0 * 2f32.powi(-14),
+        0x1F if mantissa == 0.0 => f32::INFINITY,
+        0x1F => f32::NAN,
+        exponent => (1.0 + mantissa / 1024.0) * 2f32.powi(exponent as i32 - 15),
+    };
+    if v & 0x8000 != 0 { -value } else { value }
+}
+
+// Rounds to the nearest half-precision float, out of range values becoming infinite
+fn encode_half_float(v: f32) -> u16 {
+    let sign = if v.is_sign_negative() { 0x8000 } else { 0 };
+    let v = v.abs();
+    if v.is_nan() {
+        return 0x7E00;
+    }
+    if v < 2f32.powi(-14) {
+        // Subnormal, which rounds up to the smallest normal value as expected
+        return sign | (v / 2f32.powi(-24)).round() as u16;
+    }
+    let exponent = ((v.to_bits() >> 23) & 0xFF) as i32 - 127;
+    let mantissa = ((v / 2f32.powi(exponent) - 1.0) * 1024.0).round() as u16;
+    let (exponent, mantissa) = if mantissa == 1024 { (exponent + 1, 0) } else { (exponent, mantissa) };
+    if exponent > 15 {
+        return sign | 0x7C00;
+    }
+    sign | ((exponent + 15) as u16) << 10 | mantissa
+}
+
+/// Display Parameters of DisplayID 2.x.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DisplayParametersV2 {
+	/// Image size in millimeters.
+	pub width: f32,
+	pub height: f32,
+	pub horizontal_pixels: u16,
+	pub vertical_pixels: u16,
+	/// Feature support flags, e.g. audio support and scan orientation.
+	pub features: u8,
+	pub red: ChromaticityPoint,
+	pub green: ChromaticityPoint,
+	pub blue: ChromaticityPoint,
+	pub white: ChromaticityPoint,
+	/// Luminances in cd/m², the maximum over the full screen and over 10% of it.
+	pub max_luminance: f32,
+	pub max_luminance_10_percent: f32,
+	pub min_luminance: f32,
+	/// Native bits per color component, if defined.
+	pub bits_per_color: Option<u8>,
+	/// Display technology code, e.g. 1 for an AMLCD or 2 for an AMOLED.
+	pub technology: u8,
+	pub gamma: Option<f32>,
+}
+
+pub(crate) fn parse_display_parameters_v2(input: &[u8], revision: u8) -> IResult<&[u8], DisplayParametersV2> {
+    let (input, (width, height, horizontal_pixels, vertical_pixels, features)) =
+        tuple((le_u16, le_u16, le_u16, le_u16, le_u8))(input)?;
+    let (input, (red, green, blue, white)) = tuple((
+        parse_chromaticity_point,
+        parse_chromaticity_point,
+        parse_chromaticity_point,
+        parse_chromaticity_point,
+    ))(input)?;
+    let (input, (max_luminance, max_luminance_10_percent, min_luminance, depth, gamma)) =
+        tuple((le_u16, le_u16, le_u16, le_u8, le_u8))(input)?;
+
+    // The image size is in units of 0.1 mm, or 1 mm when the revision flags say so
+    let size = |v: u16| if revision & 0x80 != 0 { v as f32 } else { v as f32 / 10.0 };
+    Ok((
+        input,
+        DisplayParametersV2 {
+            width: size(width),
+            height: size(height),
+            horizontal_pixels: horizontal_pixels.saturating_add(1),
+            vertical_pixels: vertical_pixels.saturating_add(1),
+            features,
+            red,
+            green,
+            blue,
+            white,
+            max_luminance: half_float(max_luminance),
+            max_luminance_10_percent: half_float(max_luminance_10_percent),
+            min_luminance: half_float(min_luminance),
+            bits_per_color: match depth & 0x7 {
+                v @ 1..=6 => Some(v * 2 + 4),
+                _ => None,
+            },
+            technology: (depth >> 4) & 0x7,
+            gamma: if gamma != 0xFF { Some((gamma as f32 + 100.0) / 100.0) } else { None },
+        },
+    ))
+}
+
+/// Encodes display parameters, and returns them with the block revision flags. The image size is
+/// in units of 0.1 mm unless it doesn't fit.
+pub(crate) fn encode_display_parameters_v2(parameters: &DisplayParametersV2) -> (u8, Vec<u8>) {
+    let fits = parameters.width.max(parameters.height) * 10.0 < 65535.5;
+    let (revision, scale) = if fits { (0x00, 10.0) } else { (0x80, 1.0) };
+
+    let mut out = Vec::new();
+    for v in &[
+        scale_u16(parameters.width, scale),
+        scale_u16(parameters.height, scale),
+        parameters.horizontal_pixels.saturating_sub(1),
+        parameters.vertical_pixels.saturating_sub(1),
+    ] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+    out.push(parameters.features);
+    for point in &[parameters.red, parameters.green, parameters.blue, parameters.white] {
+        out.extend_from_slice(&encode_chromaticity_point(point));
+    }
+    for v in &[parameters.max_luminance, parameters.max_luminance_10_percent, parameters.min_luminance] {
+        out.extend_from_slice(&encode_half_float(*v).to_le_bytes());
+    }
+    let depth = parameters.bits_per_color.map_or(0, |bits| (bits.saturating_sub(4) / 2).min(7));
+    out.extend_from_slice(&[(parameters.technology & 0x7) << 4 | depth, encode_gamma(parameters.gamma)]);
+    (revision, out)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColorCharacteristics {
+	/// The primaries are displayed one after the other rather than side by side.
+	pub temporal: bool,
+	pub primaries: Vec<ChromaticityPoint>,
+	pub white_points: Vec<ChromaticityPoint>,
+}
+
+// Coordinates are 12-bit fractions, packed by pairs in 3 bytes
+pub(crate) fn parse_chromaticity_point(input: &[u8]) -> IResult<&[u8], ChromaticityPoint> {
+    map(tuple((le_u8, le_u8, le_u8)), |(b0, b1, b2)| ChromaticityPoint {
+        x: (b0 as u16 | (b1 as u16 & 0xF) << 8) as f64 / 4096.0,
+        y: ((b1 >> 4) as u16 | (b2 as u16) << 4) as f64 / 4096.0,
+    })(input)
+}
+
+pub(crate) fn parse_color_characteristics(input: &[u8]) -> IResult<&[u8], ColorCharacteristics> {
+    let (input, b) = le_u8(input)?;
+    let (input, primaries) = count(parse_chromaticity_point, (b & 0xF) as usize)(input)?;
+    let (input, white_points) = count(parse_chromaticity_point, ((b >> 4) & 0x7) as usize)(input)?;
+    Ok((
+        input,
+        ColorCharacteristics {
+            temporal: b & 0x80 != 0,
+            primaries,
+            white_points,
+        },
+    ))
+}
+
+pub(crate) fn encode_chromaticity_point(point: &ChromaticityPoint) -> [u8; 3] {
+    let coordinate = |v: f64| (v * 4096.0).round().clamp(0.0, 4095.0) as u16;
+    let (x, y) = (coordinate(point.x), coordinate(point.y));
+    [x as u8, (x >> 8) as u8 | ((y & 0xF) as u8) << 4, (y >> 4) as u8]
+}
+
+pub(crate) fn encode_color_characteristics(characteristics: &ColorCharacteristics) -> Vec<u8> {
+    let mut out = vec![
+        (characteristics.temporal as u8) << 7
+            | (characteristics.white_points.len().min(7) as u8) << 4
+            | characteristics.primaries.len().min(15) as u8,
+    ];
+    for point in characteristics.primaries.iter().take(15).chain(characteristics.white_points.iter().take(7)) {
+        out.extend_from_slice(&encode_chromaticity_point(point));
+    }
+    out
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DisplayDevice {
+	/// Display technology code, e.g. 0x14 for a TN active matrix LCD.
+	pub technology: u8,
+	/// Operating mode and backlight.
+	pub operating_mode: u8,
+	pub horizontal_pixels: u16,
+	pub vertical_pixels: u16,
+	pub aspect_ratio: f32,
+	/// Orientation, rotation, zero pixel location and scan direction.
+	pub orientation: u8,
+	pub subpixel_layout: u8,
+	/// Pixel pitches in millimeters.
+	pub horizontal_pitch: f32,
+	pub vertical_pitch: f32,
+	pub bits_per_color: u8,
+	/// Response time in milliseconds.
+	pub response_time: u8,
+	/// The response time is measured from black to white, rather than white to black.
+	pub response_time_black_to_white: bool,
+}
+
+pub(crate) fn parse_display_device(input: &[u8]) -> IResult<&[u8], DisplayDevice> {
+    map(
+        tuple((le_u8, le_u8, le_u16, le_u16, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8)),
+        |(technology, operating_mode, h, v, aspect_ratio, orientation, subpixel_layout, h_pitch, v_pitch, depth, response)| {
+            DisplayDevice {
+                technology,
+                operating_mode,
+                horizontal_pixels: h.saturating_add(1),
+                vertical_pixels: v.saturating_add(1),
+                aspect_ratio: aspect_ratio as f32 / 100.0 + 1.0,
+                orientation,
+                subpixel_layout,
+                horizontal_pitch: h_pitch as f32 / 100.0,
+                vertical_pitch: v_pitch as f32 / 100.0,
+                bits_per_color: (depth & 0xF) + 1,
+                response_time: response & 0x7F,
+                response_time_black_to_white: response & 0x80 != 0,
+            }
+        },
+    )(input)
+}
+
+pub(crate) fn encode_display_device(device: &DisplayDevice) -> Vec<u8> {
+    let mut out = vec![device.technology, device.operating_mode];
+    out.extend_from_slice(&device.horizontal_pixels.saturating_sub(1).to_le_bytes());
+    out.extend_from_slice(&device.vertical_pixels.saturating_sub(1).to_le_bytes());
+    out.extend_from_slice(&[
+        scale_u8(device.aspect_ratio - 1.0, 100.0),
+        device.orientation,
+        device.subpixel_layout,
+        scale_u8(device.horizontal_pitch, 100.0),
+        scale_u8(device.vertical_pitch, 100.0),
+        device.bits_per_color.saturating_sub(1) & 0xF,
+        (device.response_time & 0x7F) | (device.response_time_black_to_white as u8) << 7,
+    ]);
+    out
+}
+
+/// Interface power sequencing, the delays are in milliseconds.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct PowerSequencing {
+	/// Range of the power supply rise time, T1.
+	pub t1_min: f32,
+	pub t1_max: u16,
+	/// Maximum delay between the power supply and the interface signals, T2.
+	pub t2_max: u16,
+	/// Maximum delay between the interface signals and the power supply going off, T3.
+	pub t3_max: u16,
+	/// Minimum power supply off time, T4.
+	pub t4_min: u16,
+	/// Minimum delay between the interface signals and the backlight, T5.
+	pub t5_min: u16,
+	/// Minimum delay between the backlight and the interface signals going off, T6.
+	pub t6_min: u16,
+}
+
+pub(crate) fn parse_power_sequencing(input: &[u8]) -> IResult<&[u8], PowerSequencing> {
+    map(
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8)),
+        |(t1, t2, t3, t4, t5, t6)| PowerSequencing {
+            t1_min: (t1 >> 4) as f32 / 10.0,
+            t1_max: (t1 & 0xF) as u16 * 2,
+            t2_max: (t2 & 0x3F) as u16 * 2,
+            t3_max: (t3 & 0x3F) as u16 * 2,
+            t4_min: (t4 & 0x7F) as u16 * 10,
+            t5_min: (t5 & 0x3F) as u16 * 10,
+            t6_min: (t6 & 0x3F) as u16 * 10,
+        },
+    )(input)
+}
+
+pub(crate) fn encode_power_sequencing(sequencing: &PowerSequencing) -> Vec<u8> {
+    vec![
+        scale_u8(sequencing.t1_min, 10.0).min(0xF) << 4 | (sequencing.t1_max / 2).min(0xF) as u8,
+        (sequencing.t2_max / 2).min(0x3F) as u8,
+        (sequencing.t3_max / 2).min(0x3F) as u8,
+        (sequencing.t4_min / 10).min(0x7F) as u8,
+        (sequencing.t5_min / 10).min(0x3F) as u8,
+        (sequencing.t6_min / 10).min(0x3F) as u8,
+    ]
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum InterfaceType {
+	Analog,
+	Lvds,
+	Tmds,
+	Rsds,
+	DviD,
+	DviIAnalog,
+	DviIDigital,
+	HdmiA,
+	HdmiB,
+	Mddi,
+	DisplayPort,
+	ProprietaryDigital,
+	Reserved(u8),
+}
+
+bitflags! {
+	/// Bits per color component.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct ColorDepths: u8 {
+		const BPC_6 = 1 << 0;
+		const BPC_8 = 1 << 1;
+		const BPC_10 = 1 << 2;
+		const BPC_12 = 1 << 3;
+		const BPC_14 = 1 << 4;
+		const BPC_16 = 1 << 5;
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ContentProtection {
+	None,
+	Hdcp,
+	Dtcp,
+	Dpcp,
+	Reserved(u8),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DisplayInterface {
+	pub interface_type: InterfaceType,
+	/// Number of links or channels, depending on the interface.
+	pub links: u8,
+	/// Version of the interface standard, major and minor.
+	pub version: (u8, u8),
+	pub rgb_depths: ColorDepths,
+	pub ycbcr444_depths: ColorDepths,
+	pub ycbcr422_depths: ColorDepths,
+	pub content_protection: ContentProtection,
+	pub content_protection_version: (u8, u8),
+	/// Spread spectrum type and percentage.
+	pub spread_spectrum: u8,
+	/// Attributes specific to the interface type.
+	pub attributes: [u8; 2],
+}
+
+pub(crate) fn parse_display_interface(input: &[u8]) -> IResult<&[u8], DisplayInterface> {
+    map(
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8)),
+        |(interface, version, rgb, ycbcr444, ycbcr422, protection, protection_version, spread_spectrum, a0, a1)| {
+            DisplayInterface {
+                interface_type: match interface >> 4 {
+                    0x0 => InterfaceType::Analog,
+                    0x1 => InterfaceType::Lvds,
+                    0x2 => InterfaceType::Tmds,
+                    0x3 => InterfaceType::Rsds,
+                    0x4 => InterfaceType::DviD,
+                    0x5 => InterfaceType::DviIAnalog,
+                    0x6 => InterfaceType::DviIDigital,
+                    0x7 => InterfaceType::HdmiA,
+                    0x8 => InterfaceType::HdmiB,
+                    0x9 => InterfaceType::Mddi,
+                    0xA => InterfaceType::DisplayPort,
+                    0xB => InterfaceType::ProprietaryDigital,
+                    v => InterfaceType::Reserved(v),
+                },
+                links: interface & 0xF,
+                version: (version >> 4, version & 0xF),
+                rgb_depths: ColorDepths::from_bits_truncate(rgb),
+                ycbcr444_depths: ColorDepths::from_bits_truncate(ycbcr444),
+                ycbcr422_depths: ColorDepths::from_bits_truncate(ycbcr422),
+                content_protection: match protection & 0x7 {
+                    0 => ContentProtection::None,
+                    1 => ContentProtection::Hdcp,
+                    2 => ContentProtection::Dtcp,
+                    3 => ContentProtection::Dpcp,
+                    v => ContentProtection::Reserved(v),
+                },
+                content_protection_version: (protection_version >> 4, protection_version & 0xF),
+                spread_spectrum,
+                attributes: [a0, a1],
+            }
+        },
+    )(input)
+}
+
+pub(crate) fn encode_display_interface(interface: &DisplayInterface) -> Vec<u8> {
+    let interface_type = match interface.interface_type {
+        InterfaceType::Analog => 0x0,
+        InterfaceType::Lvds => 0x1,
+        InterfaceType::Tmds => 0x2,
+        InterfaceType::Rsds => 0x3,
+        InterfaceType::DviD => 0x4,
+        InterfaceType::DviIAnalog => 0x5,
+        InterfaceType::DviIDigital => 0x6,
+        InterfaceType::HdmiA => 0x7,
+        InterfaceType::HdmiB => 0x8,
+        InterfaceType::Mddi => 0x9,
+        InterfaceType::DisplayPort => 0xA,
+        InterfaceType::ProprietaryDigital => 0xB,
+        InterfaceType::Reserved(v) => v & 0xF,
+    };
+    let content_protection = match interface.content_protection {
+        ContentProtection::None => 0,
+        ContentProtection::Hdcp => 1,
+        ContentProtection::Dtcp => 2,
+        ContentProtection::Dpcp => 3,
+        ContentProtection::Reserved(v) => v & 0x7,
+    };
+    let version = |(major, minor): (u8, u8)| major << 4 | (minor & 0xF);
+    vec![
+        interface_type << 4 | (interface.links & 0xF),
+        version(interface.version),
+        interface.rgb_depths.bits(),
+        interface.ycbcr444_depths.bits(),
+        interface.ycbcr422_depths.bits(),
+        content_protection,
+        version(interface.content_protection_version),
+        interface.spread_spectrum,
+        interface.attributes[0],
+        interface.attributes[1],
+    ]
+}
+
+bitflags! {
+	/// Combinations of color space and EOTF.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct ColorSpaceEotfs: u8 {
+		const SRGB = 1 << 0;
+		const BT601 = 1 << 1;
+		/// BT.709 with the BT.1886 EOTF.
+		const BT709 = 1 << 2;
+		const ADOBE_RGB = 1 << 3;
+		const DCI_P3 = 1 << 4;
+		const BT2020 = 1 << 5;
+		/// BT.2020 with the SMPTE ST 2084 EOTF.
+		const BT2020_PQ = 1 << 6;
+	}
+}
+
+/// Display Interface Features of DisplayID 2.x.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DisplayInterfaceFeatures {
+	pub rgb_depths: ColorDepths,
+	pub ycbcr444_depths: ColorDepths,
+	pub ycbcr422_depths: ColorDepths,
+	pub ycbcr420_depths: ColorDepths,
+	/// Minimum pixel rate in kHz above which YCbCr 4:2:0 is supported for all modes.
+	pub ycbcr420_min_pixel_rate: Option<u32>,
+	/// Supported audio sample rates and their frame lengths.
+	pub audio: u8,
+	pub color_space_eotfs: ColorSpaceEotfs,
+	/// Additional color space and EOTF combinations, one code per byte.
+	pub additional_color_space_eotfs: Vec<u8>,
+}
+
+pub(crate) fn parse_display_interface_features(input: &[u8]) -> IResult<&[u8], DisplayInterfaceFeatures> {
+    let (input, (rgb, ycbcr444, ycbcr422, ycbcr420, min_rate, audio, combinations, _, additional)) =
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8))(input)?;
+    let (input, additional) = take(additional & 0x7)(input)?;
+    Ok((
+        input,
+        DisplayInterfaceFeatures {
+            rgb_depths: ColorDepths::from_bits_truncate(rgb),
+            ycbcr444_depths: ColorDepths::from_bits_truncate(ycbcr444),
+            ycbcr422_depths: ColorDepths::from_bits_truncate(ycbcr422),
+            ycbcr420_depths: ColorDepths::from_bits_truncate(ycbcr420),
+            // In units of 74.25 MHz
+            ycbcr420_min_pixel_rate: if min_rate != 0 { Some(min_rate as u32 * 74_250) } else { None },
+            audio,
+            color_space_eotfs: ColorSpaceEotfs::from_bits_truncate(combinations),
+            additional_color_space_eotfs: additional.to_vec(),
+        },
+    ))
+}
+
+pub(crate) fn encode_display_interface_features(features: &DisplayInterfaceFeatures) -> Vec<u8> {
+    let additional = &features.additional_color_space_eotfs[..features.additional_color_space_eotfs.len().min(7)];
+    let mut out = vec![
+        features.rgb_depths.bits(),
+        features.ycbcr444_depths.bits(),
+        features.ycbcr422_depths.bits(),
+        features.ycbcr420_depths.bits(),
+        features.ycbcr420_min_pixel_rate.map_or(0, |rate| (rate / 74_250).min(0xFF) as u8),
+        features.audio,
+        features.color_space_eotfs.bits(),
+        0,
+        additional.len() as u8,
+    ];
+    out.extend_from_slice(additional);
+    out
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StereoDisplayInterface {
+	/// Which timings support stereo, from the block revision flags.
+	pub timing_support: u8,
+	/// Stereo interface method code, e.g. 0 for field sequential.
+	pub method: u8,
+	pub parameters: Vec<u8>,
+}
+
+pub(crate) fn stereo_display_interface(flags: u8, input: &[u8]) -> IResult<&[u8], StereoDisplayInterface> {
+    let (input, method) = le_u8(input)?;
+    Ok((
+        &input[input.len()..],
+        StereoDisplayInterface {
+            timing_support: flags >> 6,
+            method,
+            parameters: input.to_vec(),
+        },
+    ))
+}
+
+/// Encodes a stereo display interface, and returns it with the block revision flags.
+pub(crate) fn encode_stereo_display_interface(interface: &StereoDisplayInterface) -> (u8, Vec<u8>) {
+    let mut out = vec![interface.method];
+    out.extend_from_slice(&interface.parameters);
+    (interface.timing_support << 6, out)
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum TileBehavior {
+	Undefined,
+	/// The image is displayed at the location of the tile.
+	TileLocation,
+	/// The image is scaled to fit the whole display.
+	ScaleToFit,
+	/// The image is cloned on all the tiles.
+	Cloned,
+	Reserved(u8),
+}
+
+/// Bezel sizes in pixels.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct TileBezel {
+	/// Pixel multiplier in tenths, the sizes being stored in units of it.
+	pub pixel_multiplier: u8,
+	pub top: f32,
+	pub bottom: f32,
+	pub right: f32,
+	pub left: f32,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct TiledDisplayTopology {
+	/// All the tiles are in a single physical enclosure.
+	pub single_enclosure: bool,
+	/// Behavior when only this tile receives an image.
+	pub single_tile_behavior: TileBehavior,
+	/// Behavior when some tiles but not all receive an image.
+	pub multiple_tile_behavior: TileBehavior,
+	/// Number of tiles of the whole display.
+	pub horizontal_tiles: u8,
+	pub vertical_tiles: u8,
+	/// Location of this tile, starting at 0 from the top left.
+	pub horizontal_location: u8,
+	pub vertical_location: u8,
+	/// Size of this tile in pixels.
+	pub tile_width: u16,
+	pub tile_height: u16,
+	pub bezel: Option<TileBezel>,
+	/// Identification of the whole display, shared by all its tiles.
+	pub vendor_id: [u8; 3],
+	pub product_code: u16,
+	pub serial: u32,
+}
+
+fn tile_behavior(v: u8) -> TileBehavior {
+    match v {
+        0 => TileBehavior::Undefined,
+        1 => TileBehavior::TileLocation,
+        2 => TileBehavior::ScaleToFit,
+        3 => TileBehavior::Cloned,
+        v => TileBehavior::Reserved(v),
+    }
+}
+
+fn encode_tile_behavior(behavior: TileBehavior) -> u8 {
+    match behavior {
+        TileBehavior::Undefined => 0,
+        TileBehavior::TileLocation => 1,
+        TileBehavior::ScaleToFit => 2,
+        TileBehavior::Cloned => 3,
+        TileBehavior::Reserved(v) => v,
+    }
+}
+
+pub(crate) fn parse_tiled_display_topology(input: &[u8]) -> IResult<&[u8], TiledDisplayTopology> {
+    let (input, (capabilities, tiles, location, high, width, height, multiplier)) =
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u16, le_u16, le_u8))(input)?;
+    let (input, (bezels, vendor_id, product_code, serial)) =
+        tuple((take(4usize), take(3usize), le_u16, le_u32))(input)?;
+
+    // Bezel sizes are in units of a tenth of the pixel multiplier
+    let bezel = |v: u8| v as f32 * multiplier as f32 / 10.0;
+    Ok((
+        input,
+        TiledDisplayTopology {
+            single_enclosure: capabilities & 0x80 != 0,
+            single_tile_behavior: tile_behavior(capabilities & 0x7),
+            multiple_tile_behavior: tile_behavior((capabilities >> 3) & 0x3),
+            horizontal_tiles: ((tiles >> 4) | (high >> 6) << 4) + 1,
+            vertical_tiles: ((tiles & 0xF) | ((high >> 4) & 0x3) << 4) + 1,
+            horizontal_location: (location >> 4) | ((high >> 2) & 0x3) << 4,
+            vertical_location: (location & 0xF) | (high & 0x3) << 4,
+            tile_width: width.saturating_add(1),
+            tile_height: height.saturating_add(1),
+            bezel: if capabilities & 0x40 != 0 {
+                Some(TileBezel {
+                    pixel_multiplier: multiplier,
+                    top: bezel(bezels[0]),
+                    bottom: bezel(bezels[1]),
+                    right: bezel(bezels[2]),
+                    left: bezel(bezels[3]),
+                })
+            } else {
+                None
+            },
+            vendor_id: [vendor_id[0], vendor_id[1], vendor_id[2]],
+            product_code,
+            serial,
+        },
+    ))
+}
+
+pub(crate) fn encode_tiled_display_topology(topology: &TiledDisplayTopology) -> Vec<u8> {
+    let tiles = (topology.horizontal_tiles.saturating_sub(1), topology.vertical_tiles.saturating_sub(1));
+    let location = (topology.horizontal_location, topology.vertical_location);
+    let multiplier = topology.bezel.map_or(0, |bezel| bezel.pixel_multiplier);
+    let bezels = match topology.bezel {
+        Some(bezel) if multiplier != 0 => {
+            let size = |v: f32| scale_u8(v * 10.0, 1.0 / multiplier as f32);
+            [size(bezel.top), size(bezel.bottom), size(bezel.right), size(bezel.left)]
+        }
+        _ => [0; 4],
+    };
+
+    let mut out = vec![
+        (topology.single_enclosure as u8) << 7
+            | (topology.bezel.is_some() as u8) << 6
+            | (encode_tile_behavior(topology.multiple_tile_behavior) & 0x3) << 3
+            | (encode_tile_behavior(topology.single_tile_behavior) & 0x7),
+        (tiles.0 & 0xF) << 4 | (tiles.1 & 0xF),
+        (location.0 & 0xF) << 4 | (location.1 & 0xF),
+        (tiles.0 >> 4) << 6 | (tiles.1 >> 4 & 0x3) << 4 | (location.0 >> 4 & 0x3) << 2 | (location.1 >> 4 & 0x3),
+    ];
+    out.extend_from_slice(&topology.tile_width.saturating_sub(1).to_le_bytes());
+    out.extend_from_slice(&topology.tile_height.saturating_sub(1).to_le_bytes());
+    out.push(multiplier);
+    out.extend_from_slice(&bezels);
+    out.extend_from_slice(&topology.vendor_id);
+    out.extend_from_slice(&topology.product_code.to_le_bytes());
+    out.extend_from_slice(&topology.serial.to_le_bytes());
+    out
+}
+
+/// Adaptive-Sync descriptor of DisplayID 2.x.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct AdaptiveSync {
+	/// Support flags, e.g. fixed average refresh rate and seamless transitions.
+	pub flags: u8,
+	/// Maximum single frame duration increase and decrease in milliseconds.
+	pub max_duration_increase: f32,
+	pub max_duration_decrease: f32,
+	/// Vertical refresh rates in Hz.
+	pub min_refresh_rate: u8,
+	pub max_refresh_rate: u16,
+}
+
+/// Parses 6-byte Adaptive-Sync descriptors.
+pub(crate) fn parse_adaptive_sync(input: &[u8]) -> IResult<&[u8], Vec<AdaptiveSync>> {
+    all_consuming(many0(map(
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8)),
+        |(flags, increase, min_rate, max_lo, max_hi, decrease)| AdaptiveSync {
+            flags,
+            max_duration_increase: increase as f32 / 4.0,
+            max_duration_decrease: decrease as f32 / 4.0,
+            min_refresh_rate: min_rate,
+            max_refresh_rate: (max_lo as u16 | (max_hi as u16 & 0x3) << 8) + 1,
+        },
+    )))(input)
+}
+
+pub(crate) fn encode_adaptive_sync(descriptors: &[AdaptiveSync]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for descriptor in descriptors {
+        let max_rate = descriptor.max_refresh_rate.saturating_sub(1);
+        out.extend_from_slice(&[
+            descriptor.flags,
+            scale_u8(descriptor.max_duration_increase, 4.0),
+            descriptor.min_refresh_rate,
+            max_rate as u8,
+            (max_rate >> 8) as u8 & 0x3,
+            scale_u8(descriptor.max_duration_decrease, 4.0),
+        ]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_product_identification() {
+		let d = [0x41, 0x42, 0x43, 0x34, 0x12, 0x78, 0x56, 0x34, 0x12, 0xFF, 0x18, 0x05, 0x50, 0x61, 0x6E, 0x65, 0x6C];
+
+		let product = parse_product_identification(&d).unwrap().1;
+		assert_eq!(product, ProductIdentification {
+			vendor_id: *b"ABC",
+			product_code: 0x1234,
+			serial: 0x12345678,
+			date: ManufactureDate::ModelYear(2024),
+			name: "Panel".to_string(),
+		});
+		assert!(parse_product_identification(&d[..16]).is_err());
+	}
+
+	#[test]
+	fn test_tiled_display_topology() {
+		let d = [
+			0xC1, 0x10, 0x10, 0x00, 0xFF, 0x0E, 0x6F, 0x08, 0x0A, 0x00, 0x00, 0x14, 0x14, 0x44, 0x45, 0x4C,
+			0x34, 0x12, 0x01, 0x00, 0x00, 0x00,
+		];
+
+		let tile = parse_tiled_display_topology(&d).unwrap().1;
+		assert!(tile.single_enclosure);
+		assert_eq!(tile.single_tile_behavior, TileBehavior::TileLocation);
+		assert_eq!((tile.horizontal_tiles, tile.vertical_tiles), (2, 1));
+		assert_eq!((tile.horizontal_location, tile.vertical_location), (1, 0));
+		assert_eq!((tile.tile_width, tile.tile_height), (3840, 2160));
+		assert_eq!(tile.bezel, Some(TileBezel { pixel_multiplier: 10, top: 0.0, bottom: 0.0, right: 20.0, left: 20.0 }));
+		assert_eq!(encode_tiled_display_topology(&tile), d);
+		assert_eq!((tile.vendor_id, tile.product_code, tile.serial), (*b"DEL", 0x1234, 1));
+	}
+
+	#[test]
+	fn test_display_parameters_v2() {
+		let d = [
+			0x8C, 0x0A, 0xF4, 0x05, 0xFF, 0x0E, 0x6F, 0x08, 0x00, 0xB8, 0x52, 0xA5, 0xCD, 0xCC, 0xCC, 0x66, 0x06, 0x0F,
+			0x0C, 0x02, 0x54, 0x00, 0x64, 0x00, 0x66, 0x00, 0x00, 0x13, 0x78,
+		];
+
+		let parameters = parse_display_parameters_v2(&d, 0).unwrap().1;
+		assert_eq!((parameters.width, parameters.height), (270.0, 152.4));
+		assert_eq!((parameters.horizontal_pixels, parameters.vertical_pixels), (3840, 2160));
+		assert_eq!(parameters.max_luminance, 1024.0);
+		assert_eq!(parameters.max_luminance_10_percent, 1536.0);
+		assert_eq!(parameters.min_luminance, 0.0);
+		assert_eq!((parameters.bits_per_color, parameters.technology), (Some(10), 1));
+		assert_eq!(parameters.gamma, Some(2.2));
+	}
+
+	#[test]
+	fn test_adaptive_sync() {
+		let d = [0x01, 0x10, 0x30, 0x8F, 0x00, 0x08, 0x00, 0x10, 0x30, 0x2B, 0x01, 0x08];
+
+		let descriptors = parse_adaptive_sync(&d).unwrap().1;
+		assert_eq!(descriptors.len(), 2);
+		assert_eq!(descriptors[0], AdaptiveSync {
+			flags: 0x01,
+			max_duration_increase: 4.0,
+			max_duration_decrease: 2.0,
+			min_refresh_rate: 48,
+			max_refresh_rate: 144,
+		});
+		assert_eq!(descriptors[1].max_refresh_rate, 300);
+		assert!(parse_adaptive_sync(&d[..11]).is_err());
+	}
+}
@@ -0,0 +1,197 @@
+//! Grouping of the EDIDs of a tiled display, each tile being driven by its own connector.
+
+use super::super::{Extension, EDID};
+use super::{DataBlock, TileBezel, TiledDisplayTopology};
+
+/// Tile of a tiled display, as described by the EDID of one connector.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Tile {
+	/// Index of the EDID of this tile.
+	pub edid: usize,
+	/// Location of this tile, starting at 0 from the top left.
+	pub horizontal_location: u8,
+	pub vertical_location: u8,
+	/// Size of this tile in pixels.
+	pub width: u16,
+	pub height: u16,
+	pub bezel: Option<TileBezel>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TiledDisplay {
+	/// Identification of the whole display, shared by all its tiles.
+	pub vendor_id: [u8; 3],
+	pub product_code: u16,
+	pub serial: u32,
+	/// Number of tiles of the whole display.
+	pub horizontal_tiles: u8,
+	pub vertical_tiles: u8,
+	/// All the tiles are in a single physical enclosure.
+	pub single_enclosure: bool,
+	/// Tiles sorted by row then column.
+	pub tiles: Vec<Tile>,
+}
+
+impl TiledDisplay {
+	/// Returns whether each location of the grid has exactly one tile.
+	pub fn is_complete(&self) -> bool {
+		let locations = self.horizontal_tiles as usize * self.vertical_tiles as usize;
+		let in_grid = |tile: &Tile| {
+			tile.horizontal_location < self.horizontal_tiles && tile.vertical_location < self.vertical_tiles
+		};
+		self.tiles.len() == locations
+			&& self.tiles.iter().all(in_grid)
+			&& self.tiles.windows(2).all(|pair| location(&pair[0]) != location(&pair[1]))
+	}
+
+	/// Returns the offset in pixels of a tile from the top left of the display, from the sizes of
+	/// the tiles above and left of it.
+	pub fn tile_offset(&self, tile: &Tile) -> (u32, u32) {
+		let x = self
+			.tiles
+			.iter()
+			.filter(|t| t.vertical_location == tile.vertical_location && t.horizontal_location < tile.horizontal_location)
+			.map(|t| t.width as u32)
+			.sum();
+		let y = self
+			.tiles
+			.iter()
+			.filter(|t| t.horizontal_location == tile.horizontal_location && t.vertical_location < tile.vertical_location)
+			.map(|t| t.height as u32)
+			.sum();
+		(x, y)
+	}
+
+	/// Returns the size of the whole display in pixels, if it is complete.
+	pub fn size(&self) -> Option<(u32, u32)> {
+		if !self.is_complete() {
+			return None;
+		}
+		let last = self.tiles.last()?;
+		let (x, y) = self.tile_offset(last);
+		Some((x + last.width as u32, y + last.height as u32))
+	}
+}
+
+fn location(tile: &Tile) -> (u8, u8) {
+    (tile.vertical_location, tile.horizontal_location)
+}
+
+fn tiled_display_topology(edid: &EDID) -> Option<&TiledDisplayTopology> {
+    edid.extensions
+        .iter()
+        .filter_map(|extension| match *extension {
+            Extension::DisplayId(ref displayid) => Some(&displayid.data_blocks),
+            _ => None,
+        })
+        .flat_map(|data_blocks| data_blocks.iter())
+        .find_map(|block| match *block {
+            DataBlock::TiledDisplayTopology(_, ref topology) => Some(topology),
+            _ => None,
+        })
+}
+
+/// Groups the EDIDs of several connectors into tiled displays, from their DisplayID tiled display
+/// topology blocks. Tiles are grouped by the identification of the whole display and the size of
+/// its grid, EDIDs without a topology block are ignored.
+pub fn tiled_displays(edids: &[EDID]) -> Vec<TiledDisplay> {
+    let mut displays: Vec<TiledDisplay> = Vec::new();
+    for (i, edid) in edids.iter().enumerate() {
+        let topology = match tiled_display_topology(edid) {
+            Some(topology) => topology,
+            None => continue,
+        };
+        let tile = Tile {
+            edid: i,
+            horizontal_location: topology.horizontal_location,
+            vertical_location: topology.vertical_location,
+            width: topology.tile_width,
+            height: topology.tile_height,
+            bezel: topology.bezel,
+        };
+
+        let group = displays.iter().position(|display| {
+            (display.vendor_id, display.product_code, display.serial)
+                == (topology.vendor_id, topology.product_code, topology.serial)
+                && (display.horizontal_tiles, display.vertical_tiles)
+                    == (topology.horizontal_tiles, topology.vertical_tiles)
+        });
+        match group {
+            Some(group) => {
+                let display = &mut displays[group];
+                display.single_enclosure &= topology.single_enclosure;
+                display.tiles.push(tile);
+            }
+            None => displays.push(TiledDisplay {
+                vendor_id: topology.vendor_id,
+                product_code: topology.product_code,
+                serial: topology.serial,
+                horizontal_tiles: topology.horizontal_tiles,
+                vertical_tiles: topology.vertical_tiles,
+                single_enclosure: topology.single_enclosure,
+                tiles: vec![tile],
+            }),
+        }
+    }
+
+    for display in &mut displays {
+        display.tiles.sort_by_key(location);
+    }
+    displays
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::super::{checksum, parse};
+	use super::*;
+
+	// Returns the EDID of the tile at this location of a 2x1 display
+	fn tiled_edid(location: u8, serial: u8) -> EDID {
+		let mut d = include_bytes!("../../testdata/card0-eDP-1").to_vec();
+		d[0x7E] = 1;
+		d[0x7F] = checksum(&d[..0x7F]);
+
+		let mut block = vec![0x70, 0x12, 25, 0x03, 0x00, 0x12, 0x00, 22];
+		block.extend_from_slice(&[
+			0xC1, 0x10, location << 4, 0x00, 0xFF, 0x0E, 0x6F, 0x08, 0x0A, 0x00, 0x00, 0x14, 0x14, 0x44, 0x45,
+			0x4C, 0x34, 0x12, serial, 0x00, 0x00, 0x00,
+		]);
+		let section_checksum = checksum(&block[1..]);
+		block.push(section_checksum);
+		block.resize(127, 0);
+		let block_checksum = checksum(&block);
+		block.push(block_checksum);
+		d.extend(block);
+		parse(&d).unwrap()
+	}
+
+	#[test]
+	fn test_tiled_displays() {
+		let edids = [
+			tiled_edid(1, 1),
+			parse(include_bytes!("../../testdata/card0-eDP-1")).unwrap(),
+			tiled_edid(0, 1),
+			tiled_edid(0, 2),
+		];
+
+		let displays = tiled_displays(&edids);
+		assert_eq!(displays.len(), 2);
+		let display = &displays[0];
+		assert_eq!((display.vendor_id, display.product_code, display.serial), (*b"DEL", 0x1234, 1));
+		assert!(display.single_enclosure);
+		assert_eq!(display.tiles.iter().map(|tile| tile.edid).collect::<Vec<_>>(), vec![2, 0]);
+		assert_eq!(display.tiles[1].bezel, Some(TileBezel { pixel_multiplier: 10, top: 0.0, bottom: 0.0, right: 20.0, left: 20.0 }));
+		assert_eq!(display.tile_offset(&display.tiles[1]), (3840, 0));
+		assert_eq!(display.size(), Some((7680, 2160)));
+
+		// The other tile of the second display is missing
+		assert!(!displays[1].is_complete());
+		assert_eq!(displays[1].size(), None);
+
+		// Two tiles, but one of them is out of the 2x1 grid
+		let displays = tiled_displays(&[tiled_edid(0, 3), tiled_edid(2, 3)]);
+		assert_eq!(displays[0].tiles.len(), 2);
+		assert!(!displays[0].is_complete());
+		assert_eq!(displays[0].size(), None);
+	}
+}
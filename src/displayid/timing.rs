@@ -0,0 +1,718 @@
+//! Timing data blocks of DisplayID 1.x and 2.x.
+
+use nom::bytes::complete::take;
+use nom::combinator::{all_consuming, map, map_parser};
+use nom::multi::many0;
+use nom::number::complete::{le_u16, le_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+
+use super::super::DetailedTiming;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AspectRatio {
+	Ratio1_1,
+	Ratio5_4,
+	Ratio4_3,
+	Ratio15_9,
+	Ratio16_9,
+	Ratio16_10,
+	Ratio64_27,
+	Ratio256_135,
+	Undefined,
+	Reserved(u8),
+}
+
+impl AspectRatio {
+	/// Returns the vertical size matching a horizontal size, if the ratio is defined.
+	pub fn vertical(&self, horizontal: u16) -> Option<u16> {
+		let (w, h) = match *self {
+			AspectRatio::Ratio1_1 => (1, 1),
+			AspectRatio::Ratio5_4 => (5, 4),
+			AspectRatio::Ratio4_3 => (4, 3),
+			AspectRatio::Ratio15_9 => (15, 9),
+			AspectRatio::Ratio16_9 => (16, 9),
+			AspectRatio::Ratio16_10 => (16, 10),
+			AspectRatio::Ratio64_27 => (64, 27),
+			AspectRatio::Ratio256_135 => (256, 135),
+			_ => return None,
+		};
+		Some((horizontal as u32 * h / w) as u16)
+	}
+}
+
+pub(crate) fn aspect_ratio(v: u8) -> AspectRatio {
+    match v {
+        0 => AspectRatio::Ratio1_1,
+        1 => AspectRatio::Ratio5_4,
+        2 => AspectRatio::Ratio4_3,
+        3 => AspectRatio::Ratio15_9,
+        4 => AspectRatio::Ratio16_9,
+        5 => AspectRatio::Ratio16_10,
+        6 => AspectRatio::Ratio64_27,
+        7 => AspectRatio::Ratio256_135,
+        8 => AspectRatio::Undefined,
+        v => AspectRatio::Reserved(v),
+    }
+}
+
+pub(crate) fn encode_aspect_ratio(aspect_ratio: AspectRatio) -> u8 {
+    match aspect_ratio {
+        AspectRatio::Ratio1_1 => 0,
+        AspectRatio::Ratio5_4 => 1,
+        AspectRatio::Ratio4_3 => 2,
+        AspectRatio::Ratio15_9 => 3,
+        AspectRatio::Ratio16_9 => 4,
+        AspectRatio::Ratio16_10 => 5,
+        AspectRatio::Ratio64_27 => 6,
+        AspectRatio::Ratio256_135 => 7,
+        AspectRatio::Undefined => 8,
+        AspectRatio::Reserved(v) => v & 0xF,
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum StereoSupport {
+	Mono,
+	Stereo,
+	/// Mono or stereo, depending on a user action.
+	UserSelectable,
+	Reserved,
+}
+
+fn encode_stereo_support(stereo: StereoSupport) -> u8 {
+    match stereo {
+        StereoSupport::Mono => 0,
+        StereoSupport::Stereo => 1,
+        StereoSupport::UserSelectable => 2,
+        StereoSupport::Reserved => 3,
+    }
+}
+
+/// Type I, Type II or Type VII detailed timing.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Timing {
+	/// The interlacing and the sync polarities are stored in the features, as in an EDID.
+	pub timing: DetailedTiming,
+	pub preferred: bool,
+	pub stereo: StereoSupport,
+	pub aspect_ratio: AspectRatio,
+}
+
+// Sizes of a line: active, blanking, front porch and sync width
+type TimingLine = (u16, u16, u16, u16);
+
+pub(crate) fn timing(
+    pixel_clock: u32,
+    options: u8,
+    (h_active, h_blanking, h_front_porch, h_sync): TimingLine,
+    (v_active, v_blanking, v_front_porch, v_sync): TimingLine,
+    (h_positive, v_positive): (bool, bool),
+) -> Timing {
+    // Digital separate sync, like a DTD
+    let features = 0x18 | (options & 0x10) << 3 | (v_positive as u8) << 2 | (h_positive as u8) << 1;
+    Timing {
+        timing: DetailedTiming {
+            pixel_clock,
+            horizontal_active_pixels: h_active,
+            horizontal_blanking_pixels: h_blanking,
+            vertical_active_lines: v_active,
+            vertical_blanking_lines: v_blanking,
+            horizontal_front_porch: h_front_porch,
+            horizontal_sync_width: h_sync,
+            vertical_front_porch: v_front_porch,
+            vertical_sync_width: v_sync,
+            horizontal_size: 0,
+            vertical_size: 0,
+            horizontal_border_pixels: 0,
+            vertical_border_pixels: 0,
+            features,
+        },
+        preferred: options & 0x80 != 0,
+        stereo: match (options >> 5) & 0x3 {
+            0 => StereoSupport::Mono,
+            1 => StereoSupport::Stereo,
+            2 => StereoSupport::UserSelectable,
+            _ => StereoSupport::Reserved,
+        },
+        aspect_ratio: aspect_ratio(options & 0xF),
+    }
+}
+
+// Pixel clocks are stored minus one, in units of 10 kHz in DisplayID 1.x and 1 kHz in 2.x
+fn pixel_clock(lo: u16, hi: u8, unit: u32) -> u32 {
+    (((hi as u32) << 16 | lo as u32) + 1) * unit
+}
+
+fn encode_pixel_clock(pixel_clock: u32, unit: u32, out: &mut Vec<u8>) {
+    let v = (pixel_clock / unit).saturating_sub(1);
+    out.extend_from_slice(&v.to_le_bytes()[..3]);
+}
+
+// Options byte of a detailed timing: preferred, stereo, interlacing and aspect ratio
+fn encode_timing_options(timing: &Timing) -> u8 {
+    (timing.preferred as u8) << 7
+        | encode_stereo_support(timing.stereo) << 5
+        | (timing.timing.features >> 3) & 0x10
+        | encode_aspect_ratio(timing.aspect_ratio)
+}
+
+// Sync polarities of a detailed timing, from its features
+fn sync_polarities(timing: &Timing) -> (bool, bool) {
+    (timing.timing.features & 0x02 != 0, timing.timing.features & 0x04 != 0)
+}
+
+fn encode_detailed_timing(timing: &Timing, unit: u32, out: &mut Vec<u8>) {
+    let t = &timing.timing;
+    let (h_positive, v_positive) = sync_polarities(timing);
+    let size = |v: u16| v.saturating_sub(1);
+    let offset = |v: u16, positive: bool| (v.saturating_sub(1) & 0x7FFF) | (positive as u16) << 15;
+    encode_pixel_clock(t.pixel_clock, unit, out);
+    out.push(encode_timing_options(timing));
+    for v in &[
+        size(t.horizontal_active_pixels),
+        size(t.horizontal_blanking_pixels),
+        offset(t.horizontal_front_porch, h_positive),
+        size(t.horizontal_sync_width),
+        size(t.vertical_active_lines),
+        size(t.vertical_blanking_lines),
+        offset(t.vertical_front_porch, v_positive),
+        size(t.vertical_sync_width),
+    ] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+}
+
+fn parse_detailed_timing(input: &[u8], unit: u32) -> IResult<&[u8], Timing> {
+    map(
+        tuple((
+            le_u16, le_u8, le_u8, // pixel clock and options
+            le_u16, le_u16, le_u16, le_u16, // horizontal
+            le_u16, le_u16, le_u16, le_u16, // vertical
+        )),
+        |(clock_lo, clock_hi, options, h_active, h_blanking, h_offset, h_sync, v_active, v_blanking, v_offset, v_sync)| {
+            // All the sizes are stored minus one, the sync polarities are the top bits of the offsets
+            let size = |v: u16| v.saturating_add(1);
+            timing(
+                pixel_clock(clock_lo, clock_hi, unit),
+                options,
+                (size(h_active), size(h_blanking), (h_offset & 0x7FFF) + 1, size(h_sync)),
+                (size(v_active), size(v_blanking), (v_offset & 0x7FFF) + 1, size(v_sync)),
+                (h_offset & 0x8000 != 0, v_offset & 0x8000 != 0),
+            )
+        },
+    )(input)
+}
+
+pub(crate) fn parse_type_1_timing(input: &[u8]) -> IResult<&[u8], Timing> {
+    parse_detailed_timing(input, 10)
+}
+
+pub(crate) fn parse_type_7_timing(input: &[u8]) -> IResult<&[u8], Timing> {
+    parse_detailed_timing(input, 1)
+}
+
+pub(crate) fn encode_type_1_timing(timing: &Timing, out: &mut Vec<u8>) {
+    encode_detailed_timing(timing, 10, out)
+}
+
+pub(crate) fn encode_type_7_timing(timing: &Timing, out: &mut Vec<u8>) {
+    encode_detailed_timing(timing, 1, out)
+}
+
+pub(crate) fn parse_type_2_timing(input: &[u8]) -> IResult<&[u8], Timing> {
+    map(
+        tuple((le_u16, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8)),
+        |(clock_lo, clock_hi, options, b4, b5, b6, b7, b8, b9, b10)| {
+            // The horizontal sizes are in units of 8 pixels, all sizes are stored minus one
+            let h_active = (b4 as u16 | (b5 as u16 & 0x1) << 8) * 8 + 8;
+            let v_active = (b7 as u16 | (b8 as u16 & 0xF) << 8) + 1;
+            timing(
+                pixel_clock(clock_lo, clock_hi, 10),
+                options,
+                (h_active, (b5 >> 1) as u16 * 8 + 8, (b6 >> 4) as u16 * 8 + 8, (b6 & 0xF) as u16 * 8 + 8),
+                (v_active, b9 as u16 + 1, (b10 >> 4) as u16 + 1, (b10 & 0xF) as u16 + 1),
+                (b8 & 0x80 != 0, b8 & 0x40 != 0),
+            )
+        },
+    )(input)
+}
+
+pub(crate) fn encode_type_2_timing(timing: &Timing, out: &mut Vec<u8>) {
+    let t = &timing.timing;
+    let (h_positive, v_positive) = sync_polarities(timing);
+    let cells = |v: u16| (v / 8).saturating_sub(1);
+    let lines = |v: u16| v.saturating_sub(1);
+    let h_active = cells(t.horizontal_active_pixels);
+    let v_active = lines(t.vertical_active_lines);
+    encode_pixel_clock(t.pixel_clock, 10, out);
+    out.extend_from_slice(&[
+        encode_timing_options(timing),
+        h_active as u8,
+        (h_active >> 8) as u8 & 0x1 | (cells(t.horizontal_blanking_pixels) as u8) << 1,
+        (cells(t.horizontal_front_porch) as u8) << 4 | (cells(t.horizontal_sync_width) as u8 & 0xF),
+        v_active as u8,
+        (v_active >> 8) as u8 & 0xF | (h_positive as u8) << 7 | (v_positive as u8) << 6,
+        lines(t.vertical_blanking_lines) as u8,
+        (lines(t.vertical_front_porch) as u8) << 4 | (lines(t.vertical_sync_width) as u8 & 0xF),
+    ]);
+}
+
+pub(crate) fn parse_timings(
+    input: &[u8],
+    parser: fn(&[u8]) -> IResult<&[u8], Timing>,
+) -> IResult<&[u8], Vec<Timing>> {
+    all_consuming(many0(parser))(input)
+}
+
+pub(crate) fn encode_timings(timings: &[Timing], encoder: fn(&Timing, &mut Vec<u8>)) -> Vec<u8> {
+    let mut out = Vec::new();
+    for timing in timings {
+        encoder(timing, &mut out);
+    }
+    out
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CvtFormula {
+	Standard,
+	ReducedBlanking,
+	ReducedBlankingV2,
+	ReducedBlankingV3,
+	Reserved(u8),
+}
+
+fn cvt_formula(v: u8) -> CvtFormula {
+    match v {
+        0 => CvtFormula::Standard,
+        1 => CvtFormula::ReducedBlanking,
+        2 => CvtFormula::ReducedBlankingV2,
+        3 => CvtFormula::ReducedBlankingV3,
+        v => CvtFormula::Reserved(v),
+    }
+}
+
+fn encode_cvt_formula(formula: CvtFormula) -> u8 {
+    match formula {
+        CvtFormula::Standard => 0,
+        CvtFormula::ReducedBlanking => 1,
+        CvtFormula::ReducedBlankingV2 => 2,
+        CvtFormula::ReducedBlankingV3 => 3,
+        CvtFormula::Reserved(v) => v & 0x7,
+    }
+}
+
+/// Type III short timing, to be generated with a CVT formula.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ShortTiming {
+	pub preferred: bool,
+	pub formula: CvtFormula,
+	pub aspect_ratio: AspectRatio,
+	pub horizontal_active_pixels: u16,
+	/// Derived from the horizontal active pixels and the aspect ratio, if it is defined.
+	pub vertical_active_lines: Option<u16>,
+	pub interlaced: bool,
+	/// Vertical refresh rate in Hz.
+	pub refresh_rate: u8,
+}
+
+fn parse_type_3_timing(input: &[u8]) -> IResult<&[u8], ShortTiming> {
+    map(tuple((le_u8, le_u8, le_u8)), |(options, h_active, rate)| {
+        let aspect_ratio = aspect_ratio(options & 0xF);
+        let horizontal_active_pixels = (h_active as u16 + 1) * 8;
+        ShortTiming {
+            preferred: options & 0x80 != 0,
+            // Only standard and reduced blanking are defined for Type III
+            formula: match (options >> 4) & 0x7 {
+                v @ 0..=1 => cvt_formula(v),
+                v => CvtFormula::Reserved(v),
+            },
+            aspect_ratio,
+            horizontal_active_pixels,
+            vertical_active_lines: aspect_ratio.vertical(horizontal_active_pixels),
+            interlaced: rate & 0x80 != 0,
+            refresh_rate: (rate & 0x7F) + 1,
+        }
+    })(input)
+}
+
+pub(crate) fn parse_type_3_timings(input: &[u8]) -> IResult<&[u8], Vec<ShortTiming>> {
+    all_consuming(many0(parse_type_3_timing))(input)
+}
+
+pub(crate) fn encode_type_3_timings(timings: &[ShortTiming]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for timing in timings {
+        out.extend_from_slice(&[
+            (timing.preferred as u8) << 7
+                | encode_cvt_formula(timing.formula) << 4
+                | encode_aspect_ratio(timing.aspect_ratio),
+            (timing.horizontal_active_pixels / 8).saturating_sub(1) as u8,
+            (timing.interlaced as u8) << 7 | (timing.refresh_rate.saturating_sub(1) & 0x7F),
+        ]);
+    }
+    out
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum TimingCodeType {
+	/// VESA DMT IDs.
+	Dmt,
+	/// CTA-861 VICs.
+	Vic,
+	/// HDMI VICs.
+	HdmiVic,
+	Reserved(u8),
+}
+
+/// Type IV or Type VIII timing codes, the type is given by the block revision flags.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TimingCodes {
+	pub kind: TimingCodeType,
+	pub codes: Vec<u16>,
+	/// The codes are two bytes long, which only Type VIII blocks allow.
+	pub two_byte_codes: bool,
+}
+
+/// Parses one-byte codes, or two-byte codes for Type VIII blocks whose flags say so.
+pub(crate) fn parse_timing_codes(input: &[u8], flags: u8, two_bytes: bool) -> IResult<&[u8], TimingCodes> {
+    let (input, codes) = if two_bytes {
+        all_consuming(many0(le_u16))(input)?
+    } else {
+        all_consuming(many0(map(le_u8, |code| code as u16)))(input)?
+    };
+    Ok((
+        input,
+        TimingCodes {
+            kind: match flags >> 6 {
+                0 => TimingCodeType::Dmt,
+                1 => TimingCodeType::Vic,
+                2 => TimingCodeType::HdmiVic,
+                v => TimingCodeType::Reserved(v),
+            },
+            codes,
+            two_byte_codes: two_bytes,
+        },
+    ))
+}
+
+/// Encodes timing codes, and returns them with the block revision flags giving their type. Codes
+/// are also two bytes long if they don't all fit in one.
+pub(crate) fn encode_timing_codes(codes: &TimingCodes) -> (u8, Vec<u8>) {
+    let kind = match codes.kind {
+        TimingCodeType::Dmt => 0,
+        TimingCodeType::Vic => 1,
+        TimingCodeType::HdmiVic => 2,
+        TimingCodeType::Reserved(v) => v & 0x3,
+    };
+    let mut out = Vec::new();
+    if codes.two_byte_codes || codes.codes.iter().any(|&code| code > 0xFF) {
+        for code in &codes.codes {
+            out.extend_from_slice(&code.to_le_bytes());
+        }
+        (kind << 6 | 0x08, out)
+    } else {
+        out.extend(codes.codes.iter().map(|&code| code as u8));
+        (kind << 6, out)
+    }
+}
+
+/// Lists the codes whose bits are set, the first bit standing for code 1.
+pub(crate) fn bitmap_codes(bitmap: &[u8]) -> Vec<u8> {
+    (0..bitmap.len() * 8)
+        .filter(|&i| bitmap[i / 8] & (1 << (i % 8)) != 0)
+        .map(|i| (i + 1) as u8)
+        .collect()
+}
+
+/// Builds the bitmap of a list of codes, at least `length` bytes long.
+pub(crate) fn encode_bitmap_codes(codes: &[u8], length: usize) -> Vec<u8> {
+    let length = codes.iter().map(|&code| (code as usize).div_ceil(8)).fold(length, usize::max);
+    let mut bitmap = vec![0; length];
+    for &code in codes.iter().filter(|&&code| code != 0) {
+        let i = code as usize - 1;
+        bitmap[i / 8] |= 1 << (i % 8);
+    }
+    bitmap
+}
+
+/// Type IX or Type X timing, to be generated with a CVT formula.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct FormulaTiming {
+	pub formula: CvtFormula,
+	pub horizontal_active_pixels: u16,
+	pub vertical_active_lines: u16,
+	/// Vertical refresh rate in Hz.
+	pub refresh_rate: u16,
+	/// The refresh rate is to be multiplied by 1000/1001.
+	pub fractional_refresh_rate: bool,
+	pub stereo: StereoSupport,
+}
+
+fn formula_timing(options: u8, h_active: u16, v_active: u16, refresh_rate: u16) -> FormulaTiming {
+    FormulaTiming {
+        formula: cvt_formula(options & 0x7),
+        horizontal_active_pixels: h_active.saturating_add(1),
+        vertical_active_lines: v_active.saturating_add(1),
+        refresh_rate: refresh_rate + 1,
+        fractional_refresh_rate: options & 0x10 != 0,
+        stereo: match (options >> 5) & 0x3 {
+            0 => StereoSupport::Mono,
+            1 => StereoSupport::Stereo,
+            2 => StereoSupport::UserSelectable,
+            _ => StereoSupport::Reserved,
+        },
+    }
+}
+
+fn encode_formula_timing(timing: &FormulaTiming, out: &mut Vec<u8>) {
+    out.push(
+        encode_stereo_support(timing.stereo) << 5
+            | (timing.fractional_refresh_rate as u8) << 4
+            | encode_cvt_formula(timing.formula),
+    );
+    out.extend_from_slice(&timing.horizontal_active_pixels.saturating_sub(1).to_le_bytes());
+    out.extend_from_slice(&timing.vertical_active_lines.saturating_sub(1).to_le_bytes());
+}
+
+pub(crate) fn parse_type_9_timings(input: &[u8]) -> IResult<&[u8], Vec<FormulaTiming>> {
+    all_consuming(many0(map(
+        tuple((le_u8, le_u16, le_u16, le_u8)),
+        |(options, h_active, v_active, rate)| formula_timing(options, h_active, v_active, rate as u16),
+    )))(input)
+}
+
+pub(crate) fn encode_type_9_timings(timings: &[FormulaTiming]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for timing in timings {
+        encode_formula_timing(timing, &mut out);
+        out.push(timing.refresh_rate.saturating_sub(1) as u8);
+    }
+    out
+}
+
+/// Parses Type X timings, whose descriptors have a 10-bit refresh rate when the flags give them
+/// 7 bytes rather than 6.
+pub(crate) fn parse_type_10_timings(input: &[u8], flags: u8) -> IResult<&[u8], Vec<FormulaTiming>> {
+    let size = 6 + ((flags >> 4) & 0x7) as usize;
+    all_consuming(many0(map_parser(
+        take(size),
+        map(
+            tuple((le_u8, le_u16, le_u16, le_u8, many0(le_u8))),
+            move |(options, h_active, v_active, rate_lo, rest)| {
+                let rate_hi = if size >= 7 { rest[0] & 0x3 } else { 0 };
+                formula_timing(options, h_active, v_active, rate_lo as u16 | (rate_hi as u16) << 8)
+            },
+        ),
+    )))(input)
+}
+
+/// Encodes Type X timings, and returns them with the block revision flags. Descriptors are given
+/// the 7th byte if the flags already do, or if a refresh rate needs it.
+pub(crate) fn encode_type_10_timings(timings: &[FormulaTiming], flags: u8) -> (u8, Vec<u8>) {
+    let long = flags & 0x70 != 0 || timings.iter().any(|timing| timing.refresh_rate > 0x100);
+    let mut out = Vec::new();
+    for timing in timings {
+        let rate = timing.refresh_rate.saturating_sub(1);
+        encode_formula_timing(timing, &mut out);
+        out.push(rate as u8);
+        if long {
+            out.push((rate >> 8) as u8 & 0x3);
+        }
+    }
+    (flags & !0x70 | (long as u8) << 4, out)
+}
+
+/// Video Timing Range Limits.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct RangeLimits {
+	/// Pixel clocks in kHz.
+	pub min_pixel_clock: u32,
+	pub max_pixel_clock: u32,
+	/// Horizontal rates in kHz.
+	pub min_horizontal_rate: u8,
+	pub max_horizontal_rate: u8,
+	/// Minimum horizontal blanking in pixels.
+	pub min_horizontal_blanking: u16,
+	/// Vertical rates in Hz.
+	pub min_vertical_rate: u8,
+	pub max_vertical_rate: u8,
+	/// Minimum vertical blanking in lines.
+	pub min_vertical_blanking: u16,
+	pub interlaced: bool,
+	pub cvt_standard: bool,
+	pub cvt_reduced_blanking: bool,
+	/// Only discrete frequencies in the range are supported, rather than continuous ones.
+	pub discrete_frequency: bool,
+}
+
+pub(crate) fn parse_range_limits(input: &[u8]) -> IResult<&[u8], RangeLimits> {
+    map(
+        tuple((le_u16, le_u8, le_u16, le_u8, le_u8, le_u8, le_u16, le_u8, le_u8, le_u16, le_u8)),
+        |(min_lo, min_hi, max_lo, max_hi, min_h, max_h, min_h_blanking, min_v, max_v, min_v_blanking, flags)| {
+            RangeLimits {
+                min_pixel_clock: pixel_clock(min_lo, min_hi, 10),
+                max_pixel_clock: pixel_clock(max_lo, max_hi, 10),
+                min_horizontal_rate: min_h,
+                max_horizontal_rate: max_h,
+                min_horizontal_blanking: min_h_blanking,
+                min_vertical_rate: min_v,
+                max_vertical_rate: max_v,
+                min_vertical_blanking: min_v_blanking,
+                interlaced: flags & 0x80 != 0,
+                cvt_standard: flags & 0x40 != 0,
+                cvt_reduced_blanking: flags & 0x20 != 0,
+                discrete_frequency: flags & 0x10 != 0,
+            }
+        },
+    )(input)
+}
+
+pub(crate) fn encode_range_limits(limits: &RangeLimits) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_pixel_clock(limits.min_pixel_clock, 10, &mut out);
+    encode_pixel_clock(limits.max_pixel_clock, 10, &mut out);
+    out.extend_from_slice(&[limits.min_horizontal_rate, limits.max_horizontal_rate]);
+    out.extend_from_slice(&limits.min_horizontal_blanking.to_le_bytes());
+    out.extend_from_slice(&[limits.min_vertical_rate, limits.max_vertical_rate]);
+    out.extend_from_slice(&limits.min_vertical_blanking.to_le_bytes());
+    out.push(
+        (limits.interlaced as u8) << 7
+            | (limits.cvt_standard as u8) << 6
+            | (limits.cvt_reduced_blanking as u8) << 5
+            | (limits.discrete_frequency as u8) << 4,
+    );
+    out
+}
+
+/// Dynamic Video Timing Range Limits, of DisplayID 2.x.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DynamicRangeLimits {
+	/// Pixel clocks in kHz.
+	pub min_pixel_clock: u32,
+	pub max_pixel_clock: u32,
+	/// Vertical refresh rates in Hz.
+	pub min_refresh_rate: u8,
+	pub max_refresh_rate: u16,
+	/// The vertical total can change seamlessly, without a mode change.
+	pub seamless_vtotal_change: bool,
+}
+
+pub(crate) fn parse_dynamic_range_limits(input: &[u8], revision: u8) -> IResult<&[u8], DynamicRangeLimits> {
+    map(
+        tuple((le_u16, le_u8, le_u16, le_u8, le_u8, le_u8, le_u8)),
+        |(min_lo, min_hi, max_lo, max_hi, min_rate, max_rate, flags)| {
+            // The high bits of the maximum refresh rate were added by revision 1
+            let max_rate_hi = if revision & 0x7 >= 1 { flags & 0x3 } else { 0 };
+            DynamicRangeLimits {
+                min_pixel_clock: pixel_clock(min_lo, min_hi, 1),
+                max_pixel_clock: pixel_clock(max_lo, max_hi, 1),
+                min_refresh_rate: min_rate,
+                max_refresh_rate: max_rate as u16 | (max_rate_hi as u16) << 8,
+                seamless_vtotal_change: flags & 0x80 != 0,
+            }
+        },
+    )(input)
+}
+
+/// Encodes dynamic range limits, and returns them with the block revision flags. Revision 1 is
+/// only used if the maximum refresh rate needs its high bits.
+pub(crate) fn encode_dynamic_range_limits(limits: &DynamicRangeLimits) -> (u8, Vec<u8>) {
+    let mut out = Vec::new();
+    encode_pixel_clock(limits.min_pixel_clock, 1, &mut out);
+    encode_pixel_clock(limits.max_pixel_clock, 1, &mut out);
+    out.extend_from_slice(&[
+        limits.min_refresh_rate,
+        limits.max_refresh_rate as u8,
+        (limits.seamless_vtotal_change as u8) << 7 | (limits.max_refresh_rate >> 8) as u8 & 0x3,
+    ]);
+    (if limits.max_refresh_rate > 0xFF { 1 } else { 0 }, out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_type_1_timing() {
+		// 1920x1080 at 60 Hz, preferred
+		let d = [
+			0x01, 0x3A, 0x00, 0x84, 0x7F, 0x07, 0x17, 0x01, 0x57, 0x80, 0x2B, 0x00, 0x37, 0x04, 0x2C, 0x00, 0x03,
+			0x80, 0x04, 0x00,
+		];
+
+		let timing = parse_type_1_timing(&d).unwrap().1;
+		assert!(timing.preferred);
+		assert_eq!(timing.stereo, StereoSupport::Mono);
+		assert_eq!(timing.aspect_ratio, AspectRatio::Ratio16_9);
+		assert_eq!(timing.timing.pixel_clock, 148_500);
+		assert_eq!((timing.timing.horizontal_active_pixels, timing.timing.horizontal_blanking_pixels), (1920, 280));
+		assert_eq!((timing.timing.horizontal_front_porch, timing.timing.horizontal_sync_width), (88, 44));
+		assert_eq!((timing.timing.vertical_active_lines, timing.timing.vertical_blanking_lines), (1080, 45));
+		assert_eq!((timing.timing.vertical_front_porch, timing.timing.vertical_sync_width), (4, 5));
+		assert_eq!(timing.timing.features, 0x1E);
+	}
+
+	#[test]
+	fn test_type_3_timing() {
+		let timings = parse_type_3_timings(&[0x94, 0xEF, 0x3B, 0x02, 0x7F, 0x9D]).unwrap().1;
+		assert_eq!(timings, vec![
+			ShortTiming {
+				preferred: true,
+				formula: CvtFormula::ReducedBlanking,
+				aspect_ratio: AspectRatio::Ratio16_9,
+				horizontal_active_pixels: 1920,
+				vertical_active_lines: Some(1080),
+				interlaced: false,
+				refresh_rate: 60,
+			},
+			ShortTiming {
+				preferred: false,
+				formula: CvtFormula::Standard,
+				aspect_ratio: AspectRatio::Ratio4_3,
+				horizontal_active_pixels: 1024,
+				vertical_active_lines: Some(768),
+				interlaced: true,
+				refresh_rate: 30,
+			},
+		]);
+	}
+
+	#[test]
+	fn test_bitmap_codes() {
+		assert_eq!(bitmap_codes(&[0x05, 0x00, 0x80]), vec![1, 3, 24]);
+	}
+
+	#[test]
+	fn test_type_7_timing() {
+		// 2560x1440 at 240 Hz
+		let d = [
+			0x2F, 0x1E, 0x0E, 0x03, 0xFF, 0x09, 0x9F, 0x00, 0x2F, 0x80, 0x1F, 0x00, 0x9F, 0x05, 0x27, 0x00, 0x02,
+			0x00, 0x04, 0x00,
+		];
+
+		let timing = parse_type_7_timing(&d).unwrap().1;
+		assert!(!timing.preferred);
+		assert_eq!(timing.aspect_ratio, AspectRatio::Ratio15_9);
+		assert_eq!(timing.timing.pixel_clock, 925_232);
+		assert_eq!((timing.timing.horizontal_active_pixels, timing.timing.vertical_active_lines), (2560, 1440));
+		assert_eq!(timing.timing.features, 0x1A);
+	}
+
+	#[test]
+	fn test_formula_timings() {
+		let timings = parse_type_9_timings(&[0x12, 0xFF, 0x0E, 0x6F, 0x08, 0x3B]).unwrap().1;
+		assert_eq!(timings, vec![FormulaTiming {
+			formula: CvtFormula::ReducedBlankingV2,
+			horizontal_active_pixels: 3840,
+			vertical_active_lines: 2160,
+			refresh_rate: 60,
+			fractional_refresh_rate: true,
+			stereo: StereoSupport::Mono,
+		}]);
+
+		let timings = parse_type_10_timings(&[0x03, 0xFF, 0x09, 0x9F, 0x05, 0x67, 0x01], 0x10).unwrap().1;
+		assert_eq!((timings[0].formula, timings[0].refresh_rate), (CvtFormula::ReducedBlankingV3, 360));
+		assert!(parse_type_10_timings(&[0x03, 0xFF, 0x09, 0x9F, 0x05, 0x67], 0x10).is_err());
+	}
+}
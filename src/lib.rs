@@ -1,26 +1,118 @@
+#[macro_use]
+extern crate bitflags;
 extern crate nom;
 
 use std::convert::TryInto;
+use std::{error, fmt};
 
-use nom::branch::alt;
 use nom::bytes::complete::{tag, take};
-use nom::combinator::{map, peek};
-use nom::error::VerboseError;
+use nom::combinator::{map, map_opt, peek};
+use nom::error::ParseError;
 use nom::multi::count;
 use nom::number::complete::{be_u16, le_u16, le_u32, le_u8};
-use nom::sequence::{preceded, tuple};
+use nom::sequence::{terminated, tuple};
 use nom::IResult;
 
 
+mod builder;
 mod cp437;
+pub mod cta;
+mod cvt;
+pub mod displayid;
+
+pub use builder::EdidBuilder;
+pub use cvt::cvt_timing;
+
+const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+const BLOCK_SIZE: usize = 128;
+const DESCRIPTORS_OFFSET: usize = 0x36;
+const DESCRIPTOR_SIZE: usize = 18;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+	/// The data doesn't start with the EDID header pattern.
+	InvalidHeader,
+	/// The data is shorter than the expected number of bytes.
+	Truncated { expected: usize, actual: usize },
+	/// The checksum byte doesn't match the contents of the block, or DisplayID section, at this index.
+	ChecksumMismatch { block: usize, expected: u8, actual: u8 },
+	UnsupportedVersion { version: u8, revision: u8 },
+	/// The year of manufacture is before 2006 in an EDID 1.4 or later.
+	InvalidManufactureDate { week: u8, year: u8 },
+	/// The descriptor starting at this offset of the base block can't be parsed.
+	MalformedDescriptor { offset: usize },
+	/// The extension block at this index can't be parsed.
+	MalformedExtension { block: usize },
+	/// The standalone DisplayID section at this index can't be parsed.
+	MalformedSection { section: usize },
+	/// The contents of the block at this index don't fit in it, or one of their values doesn't fit
+	/// in its field, when encoding.
+	BlockOverflow { block: usize },
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Error::InvalidHeader => write!(f, "invalid EDID header"),
+			Error::Truncated { expected, actual } => {
+				write!(f, "truncated EDID: expected {} bytes, got {}", expected, actual)
+			}
+			Error::ChecksumMismatch { block, expected, actual } => write!(
+				f,
+				"checksum mismatch in block {}: expected {:#04x}, got {:#04x}",
+				block, expected, actual
+			),
+			Error::UnsupportedVersion { version, revision } => {
+				write!(f, "unsupported EDID version {}.{}", version, revision)
+			}
+			Error::InvalidManufactureDate { week, year } => {
+				write!(f, "invalid manufacture date: week {}, year {}", week, 1990 + year as u16)
+			}
+			Error::MalformedDescriptor { offset } => {
+				write!(f, "malformed descriptor at offset {:#04x}", offset)
+			}
+			Error::MalformedExtension { block } => write!(f, "malformed extension block {}", block),
+			Error::MalformedSection { section } => write!(f, "malformed DisplayID section {}", section),
+			Error::BlockOverflow { block } => write!(f, "contents of block {} don't fit in it", block),
+		}
+	}
+}
+
+impl error::Error for Error {}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ManufactureDate {
+	Manufactured {
+		/// Week of manufacture from 1 to 54, `None` if unspecified or out of range.
+		week: Option<u8>,
+		year: u16,
+	},
+	/// The year the model was released, EDID 1.4 only.
+	ModelYear(u16),
+}
+
+fn manufacture_date(week: u8, year: u8, version: u8, revision: u8) -> Option<ManufactureDate> {
+    let edid_1_4 = (version, revision) >= (1, 4);
+    // EDID 1.4 was released in 2006, earlier years aren't allowed
+    if edid_1_4 && year < 0x10 {
+        return None;
+    }
+    let year = year as u16 + 1990; // Starting at year 1990
+
+    // Weeks out of range are common in the wild, they are treated as unspecified
+    match week {
+        1..=54 => Some(ManufactureDate::Manufactured { week: Some(week), year }),
+        0xFF if edid_1_4 => Some(ManufactureDate::ModelYear(year)),
+        _ => Some(ManufactureDate::Manufactured { week: None, year }),
+    }
+}
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct Header {
 	pub vendor: [char; 3],
 	pub product: u16,
 	pub serial: u32,
-	pub week: u8,
-	pub year: u8, // Starting at year 1990
+	pub date: ManufactureDate,
 	pub version: u8,
 	pub revision: u8,
 }
@@ -34,11 +126,15 @@ fn parse_vendor(v: u16) -> [char; 3] {
 		((v as u8 & mask) + i0) as char,
 	]
 }
-fn parse_header(input: &[u8]) -> IResult<&[u8], Header, VerboseError<&[u8]>> {
+fn encode_vendor(vendor: [char; 3]) -> u16 {
+	let letter = |c: char| ((c as u32).wrapping_sub('A' as u32 - 1) & 0x1F) as u16;
+	letter(vendor[0]) << 10 | letter(vendor[1]) << 5 | letter(vendor[2])
+}
+fn parse_header(input: &[u8]) -> IResult<&[u8], Header> {
     // Define the parsing sequence
-    map(
+    map_opt(
         tuple((
-            tag(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00]), // Match the fixed tag
+            tag(&HEADER[..]),                                       // Match the fixed tag
             be_u16,                                                 // Big-endian u16 for vendor
             le_u16,                                                 // Little-endian u16 for product
             le_u32,                                                 // Little-endian u32 for serial
@@ -47,27 +143,286 @@ fn parse_header(input: &[u8]) -> IResult<&[u8], Header, VerboseError<&[u8]>> {
             le_u8,                                                  // Little-endian u8 for version
             le_u8,                                                  // Little-endian u8 for revision
         )),
-        |(_tag, vendor, product, serial, week, year, version, revision)| Header {
-            vendor: parse_vendor(vendor),
-            product,
-            serial,
-            week,
-            year,
-            version,
-            revision,
+        |(_tag, vendor, product, serial, week, year, version, revision)| {
+            Some(Header {
+                vendor: parse_vendor(vendor),
+                product,
+                serial,
+                date: manufacture_date(week, year, version, revision)?,
+                version,
+                revision,
+            })
         },
     )(input)
 }
 
+fn encode_header(header: &Header, out: &mut Vec<u8>) {
+    let (week, year) = match header.date {
+        ManufactureDate::Manufactured { week, year } => (week.unwrap_or(0), year),
+        ManufactureDate::ModelYear(year) => (0xFF, year),
+    };
+    out.extend_from_slice(&HEADER);
+    out.extend_from_slice(&encode_vendor(header.vendor).to_be_bytes());
+    out.extend_from_slice(&header.product.to_le_bytes());
+    out.extend_from_slice(&header.serial.to_le_bytes());
+    out.extend_from_slice(&[week, year.wrapping_sub(1990) as u8, header.version, header.revision]);
+}
+
+/// Video white and sync levels, relative to blank.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SignalLevel {
+	/// +0.7/-0.3 V
+	Level0700_0300,
+	/// +0.714/-0.286 V
+	Level0714_0286,
+	/// +1.0/-0.4 V
+	Level1000_0400,
+	/// +0.7/0 V
+	Level0700_0000,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct AnalogInput {
+	pub signal_level: SignalLevel,
+	/// Blank-to-black setup (pedestal) is expected.
+	pub setup: bool,
+	pub separate_sync: bool,
+	/// Composite sync on horizontal sync.
+	pub composite_sync: bool,
+	pub sync_on_green: bool,
+	/// Serration on the vertical sync.
+	pub serration: bool,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum DigitalInterface {
+	Undefined,
+	Dvi,
+	HdmiA,
+	HdmiB,
+	Mddi,
+	DisplayPort,
+	Reserved(u8),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct DigitalInput {
+	/// Bits per primary color, `None` if undefined.
+	pub color_bit_depth: Option<u8>,
+	pub interface: DigitalInterface,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum VideoInput {
+	Analog(AnalogInput),
+	/// Digital input, EDID 1.4 and later.
+	Digital(DigitalInput),
+	/// Digital input before EDID 1.4, which only tells about DFP 1.x compatibility.
+	LegacyDigital { dfp1_compatible: bool },
+}
+
+fn video_input(header: &Header, v: u8) -> VideoInput {
+    if v & 0x80 == 0 {
+        return VideoInput::Analog(AnalogInput {
+            signal_level: match (v >> 5) & 0x3 {
+                0 => SignalLevel::Level0700_0300,
+                1 => SignalLevel::Level0714_0286,
+                2 => SignalLevel::Level1000_0400,
+                _ => SignalLevel::Level0700_0000,
+            },
+            setup: v & 0x10 != 0,
+            separate_sync: v & 0x08 != 0,
+            composite_sync: v & 0x04 != 0,
+            sync_on_green: v & 0x02 != 0,
+            serration: v & 0x01 != 0,
+        });
+    }
+
+    if (header.version, header.revision) < (1, 4) {
+        return VideoInput::LegacyDigital {
+            dfp1_compatible: v & 0x01 != 0,
+        };
+    }
+
+    VideoInput::Digital(DigitalInput {
+        color_bit_depth: match (v >> 4) & 0x7 {
+            0 | 7 => None,
+            depth => Some(depth * 2 + 4),
+        },
+        interface: match v & 0xF {
+            0 => DigitalInterface::Undefined,
+            1 => DigitalInterface::Dvi,
+            2 => DigitalInterface::HdmiA,
+            3 => DigitalInterface::HdmiB,
+            4 => DigitalInterface::Mddi,
+            5 => DigitalInterface::DisplayPort,
+            r => DigitalInterface::Reserved(r),
+        },
+    })
+}
+
+fn encode_video_input(video_input: &VideoInput) -> u8 {
+    match *video_input {
+        VideoInput::Analog(ref analog) => {
+            let signal_level = match analog.signal_level {
+                SignalLevel::Level0700_0300 => 0,
+                SignalLevel::Level0714_0286 => 1,
+                SignalLevel::Level1000_0400 => 2,
+                SignalLevel::Level0700_0000 => 3,
+            };
+            signal_level << 5
+                | (analog.setup as u8) << 4
+                | (analog.separate_sync as u8) << 3
+                | (analog.composite_sync as u8) << 2
+                | (analog.sync_on_green as u8) << 1
+                | analog.serration as u8
+        }
+        VideoInput::LegacyDigital { dfp1_compatible } => 0x80 | dfp1_compatible as u8,
+        VideoInput::Digital(ref digital) => {
+            let depth = match digital.color_bit_depth {
+                Some(depth @ 6..=16) => (depth - 4) / 2,
+                _ => 0,
+            };
+            let interface = match digital.interface {
+                DigitalInterface::Undefined => 0,
+                DigitalInterface::Dvi => 1,
+                DigitalInterface::HdmiA => 2,
+                DigitalInterface::HdmiB => 3,
+                DigitalInterface::Mddi => 4,
+                DigitalInterface::DisplayPort => 5,
+                DigitalInterface::Reserved(r) => r & 0xF,
+            };
+            0x80 | depth << 4 | interface
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum DisplayColorType {
+	Monochrome,
+	Rgb,
+	NonRgb,
+	Undefined,
+}
+
+bitflags! {
+	/// Color encodings supported on top of RGB 4:4:4.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct ColorEncodings: u8 {
+		const YCBCR_444 = 1 << 0;
+		const YCBCR_422 = 1 << 1;
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ColorSupport {
+	/// Analog displays and digital displays before EDID 1.4.
+	ColorType(DisplayColorType),
+	/// Digital displays, EDID 1.4 and later.
+	Encodings(ColorEncodings),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Features {
+	pub dpms_standby: bool,
+	pub dpms_suspend: bool,
+	pub dpms_active_off: bool,
+	pub color: ColorSupport,
+	/// sRGB is the default color space, the chromaticity matches it.
+	pub srgb_default: bool,
+	/// Before EDID 1.4, the first detailed timing is the preferred one (always set in EDID 1.3).
+	/// Starting with EDID 1.4, it is always the preferred one and this tells whether it also
+	/// includes the native pixel format and refresh rate.
+	pub preferred_timing_mode: bool,
+	/// Starting with EDID 1.4, the display supports continuous frequencies within its range
+	/// limits. Before, GTF default timings are supported.
+	pub continuous_frequency: bool,
+}
+
+fn features(video_input: &VideoInput, v: u8) -> Features {
+    let color = match *video_input {
+        VideoInput::Digital(_) => ColorSupport::Encodings(ColorEncodings::from_bits_truncate(v >> 3)),
+        _ => ColorSupport::ColorType(match (v >> 3) & 0x3 {
+            0 => DisplayColorType::Monochrome,
+            1 => DisplayColorType::Rgb,
+            2 => DisplayColorType::NonRgb,
+            _ => DisplayColorType::Undefined,
+        }),
+    };
+
+    Features {
+        dpms_standby: v & 0x80 != 0,
+        dpms_suspend: v & 0x40 != 0,
+        dpms_active_off: v & 0x20 != 0,
+        color,
+        srgb_default: v & 0x04 != 0,
+        preferred_timing_mode: v & 0x02 != 0,
+        continuous_frequency: v & 0x01 != 0,
+    }
+}
+
+fn encode_features(features: &Features) -> u8 {
+    let color = match features.color {
+        ColorSupport::Encodings(encodings) => encodings.bits(),
+        ColorSupport::ColorType(DisplayColorType::Monochrome) => 0,
+        ColorSupport::ColorType(DisplayColorType::Rgb) => 1,
+        ColorSupport::ColorType(DisplayColorType::NonRgb) => 2,
+        ColorSupport::ColorType(DisplayColorType::Undefined) => 3,
+    };
+    (features.dpms_standby as u8) << 7
+        | (features.dpms_suspend as u8) << 6
+        | (features.dpms_active_off as u8) << 5
+        | color << 3
+        | (features.srgb_default as u8) << 2
+        | (features.preferred_timing_mode as u8) << 1
+        | features.continuous_frequency as u8
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ScreenSize {
+	/// Width and height in centimeters.
+	Physical { width: u8, height: u8 },
+	/// Landscape aspect ratio as width / height, EDID 1.4 only.
+	LandscapeAspectRatio(f32),
+	/// Portrait aspect ratio as width / height, EDID 1.4 only.
+	PortraitAspectRatio(f32),
+	/// The size is unknown or variable, e.g. for projectors.
+	Undefined,
+}
+
+fn screen_size(header: &Header, width: u8, height: u8) -> ScreenSize {
+    let version = (header.version, header.revision);
+    match (width, height) {
+        (0, 0) => ScreenSize::Undefined,
+        (w, 0) if version >= (1, 4) => ScreenSize::LandscapeAspectRatio((w as f32 + 99.0) / 100.0),
+        (0, h) if version >= (1, 4) => ScreenSize::PortraitAspectRatio(100.0 / (h as f32 + 99.0)),
+        (width, height) => ScreenSize::Physical { width, height },
+    }
+}
+
+fn encode_screen_size(size: &ScreenSize) -> (u8, u8) {
+    match *size {
+        ScreenSize::Physical { width, height } => (width, height),
+        ScreenSize::LandscapeAspectRatio(ratio) => ((ratio * 100.0 - 99.0).round() as u8, 0),
+        ScreenSize::PortraitAspectRatio(ratio) => (0, (100.0 / ratio - 99.0).round() as u8),
+        ScreenSize::Undefined => (0, 0),
+    }
+}
+
+// Gammas from 1.00 to 3.54 are stored as (gamma * 100) - 100
+fn encode_gamma(gamma: Option<f32>) -> u8 {
+    gamma.map_or(0xFF, |gamma| (gamma * 100.0 - 100.0).round() as u8)
+}
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct Display {
-	pub video_input: u8,
-	pub width: u8, // cm
-	pub height: u8, // cm
-	pub gamma: u8, // datavalue = (gamma*100)-100 (range 1.00–3.54)
-	pub features: u8,
+	pub video_input: VideoInput,
+	pub size: ScreenSize,
+	/// `None` if the gamma is defined in an extension block.
+	pub gamma: Option<f32>,
+	pub features: Features,
 }
-fn parse_display(input: &[u8]) -> IResult<&[u8], Display, VerboseError<&[u8]>> {
+fn parse_display<'a>(input: &'a [u8], header: &Header) -> IResult<&'a [u8], Display> {
     map(
         tuple((
             le_u8, // Video input field
@@ -76,29 +431,278 @@ fn parse_display(input: &[u8]) -> IResult<&[u8], Display, VerboseError<&[u8]>> {
             le_u8, // Gamma
             le_u8, // Features
         )),
-        |(video_input, width, height, gamma, features)| Display {
-            video_input,
-            width,
-            height,
-            gamma,
-            features,
+        |(video_input_byte, width, height, gamma, features_byte)| {
+            let video_input = video_input(header, video_input_byte);
+            Display {
+                video_input,
+                size: screen_size(header, width, height),
+                // datavalue = (gamma*100)-100 (range 1.00–3.54)
+                gamma: if gamma == 0xFF { None } else { Some((gamma as f32 + 100.0) / 100.0) },
+                features: features(&video_input, features_byte),
+            }
         },
     )(input)
 }
 
-fn parse_chromaticity(input: &[u8]) -> IResult<&[u8], (), VerboseError<&[u8]>> {
-    let (remaining, _) = take(10usize)(input)?; // Consume 10 bytes
-    Ok((remaining, ())) // Return unit type
+fn encode_display(display: &Display, out: &mut Vec<u8>) {
+    let (width, height) = encode_screen_size(&display.size);
+    out.extend_from_slice(&[
+        encode_video_input(&display.video_input),
+        width,
+        height,
+        encode_gamma(display.gamma),
+        encode_features(&display.features),
+    ]);
+}
+
+/// A point in the CIE 1931 xy color space.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ChromaticityPoint {
+	pub x: f64,
+	pub y: f64,
+}
+
+/// Color primaries and white point of the display.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Chromaticity {
+	pub red: ChromaticityPoint,
+	pub green: ChromaticityPoint,
+	pub blue: ChromaticityPoint,
+	pub white: ChromaticityPoint,
 }
 
-fn parse_established_timing(input: &[u8]) -> IResult<&[u8], (), VerboseError<&[u8]>> {
-    let (remaining, _) = take(3usize)(input)?; // Consume 10 bytes
-    Ok((remaining, ())) // Return unit type
+// Each coordinate is a 10-bit binary fraction: the 8 high bits have their own byte, the 2 low
+// bits are packed into one of the first two bytes.
+fn chromaticity_coordinate(hi: u8, lo: u8) -> f64 {
+    (((hi as u16) << 2) | (lo & 0x3) as u16) as f64 / 1024.0
 }
 
-fn parse_standard_timing(input: &[u8]) -> IResult<&[u8], (), VerboseError<&[u8]>> {
-    let (remaining, _) = take(16usize)(input)?; // Consume 10 bytes
-    Ok((remaining, ())) // Return unit type
+fn chromaticity_point(x_hi: u8, y_hi: u8, lo: u8) -> ChromaticityPoint {
+    ChromaticityPoint {
+        x: chromaticity_coordinate(x_hi, lo >> 2),
+        y: chromaticity_coordinate(y_hi, lo),
+    }
+}
+
+fn encode_chromaticity_coordinate(v: f64) -> u16 {
+    (v * 1024.0).round().clamp(0.0, 1023.0) as u16
+}
+
+fn parse_chromaticity(input: &[u8]) -> IResult<&[u8], Chromaticity> {
+    map(
+        tuple((
+            le_u8, // red_green_lo
+            le_u8, // blue_white_lo
+            le_u8, // red_x_hi
+            le_u8, // red_y_hi
+            le_u8, // green_x_hi
+            le_u8, // green_y_hi
+            le_u8, // blue_x_hi
+            le_u8, // blue_y_hi
+            le_u8, // white_x_hi
+            le_u8, // white_y_hi
+        )),
+        |(
+            red_green_lo,
+            blue_white_lo,
+            red_x_hi,
+            red_y_hi,
+            green_x_hi,
+            green_y_hi,
+            blue_x_hi,
+            blue_y_hi,
+            white_x_hi,
+            white_y_hi,
+        )| Chromaticity {
+            red: chromaticity_point(red_x_hi, red_y_hi, red_green_lo >> 4),
+            green: chromaticity_point(green_x_hi, green_y_hi, red_green_lo),
+            blue: chromaticity_point(blue_x_hi, blue_y_hi, blue_white_lo >> 4),
+            white: chromaticity_point(white_x_hi, white_y_hi, blue_white_lo),
+        },
+    )(input)
+}
+
+fn encode_chromaticity(chromaticity: &Chromaticity, out: &mut Vec<u8>) {
+    let points = [chromaticity.red, chromaticity.green, chromaticity.blue, chromaticity.white];
+    let coordinates: Vec<(u16, u16)> = points
+        .iter()
+        .map(|point| (encode_chromaticity_coordinate(point.x), encode_chromaticity_coordinate(point.y)))
+        .collect();
+    // The 2 low bits of the x and y coordinates, packed by pairs of points
+    let lo = |(x, y): (u16, u16)| ((x & 0x3) << 2 | (y & 0x3)) as u8;
+    out.push(lo(coordinates[0]) << 4 | lo(coordinates[1]));
+    out.push(lo(coordinates[2]) << 4 | lo(coordinates[3]));
+    for &(x, y) in &coordinates {
+        out.extend_from_slice(&[(x >> 2) as u8, (y >> 2) as u8]);
+    }
+}
+
+bitflags! {
+	/// Established timings I and II, one bit per VESA mode.
+	///
+	/// The first byte is stored in the upper bits, so `bits()` reads like bytes 0x23 to 0x25.
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct EstablishedTimings: u32 {
+		const MODE_720X400_70 = 1 << 23;
+		const MODE_720X400_88 = 1 << 22;
+		const MODE_640X480_60 = 1 << 21;
+		const MODE_640X480_67 = 1 << 20;
+		const MODE_640X480_72 = 1 << 19;
+		const MODE_640X480_75 = 1 << 18;
+		const MODE_800X600_56 = 1 << 17;
+		const MODE_800X600_60 = 1 << 16;
+		const MODE_800X600_72 = 1 << 15;
+		const MODE_800X600_75 = 1 << 14;
+		const MODE_832X624_75 = 1 << 13;
+		const MODE_1024X768_87I = 1 << 12;
+		const MODE_1024X768_60 = 1 << 11;
+		const MODE_1024X768_70 = 1 << 10;
+		const MODE_1024X768_75 = 1 << 9;
+		const MODE_1280X1024_75 = 1 << 8;
+		const MODE_1152X870_75 = 1 << 7;
+		const MANUFACTURER_RESERVED_6 = 1 << 6;
+		const MANUFACTURER_RESERVED_5 = 1 << 5;
+		const MANUFACTURER_RESERVED_4 = 1 << 4;
+		const MANUFACTURER_RESERVED_3 = 1 << 3;
+		const MANUFACTURER_RESERVED_2 = 1 << 2;
+		const MANUFACTURER_RESERVED_1 = 1 << 1;
+		const MANUFACTURER_RESERVED_0 = 1 << 0;
+	}
+}
+
+// Horizontal and vertical (active, total, front porch, sync width)
+type ModeLine = (u16, u16, u16, u16);
+
+// (mode, pixel clock in kHz, horizontal, vertical, features), from the VESA DMT and the original
+// Apple/IBM modes.
+const ESTABLISHED_TIMINGS: &[(EstablishedTimings, u32, ModeLine, ModeLine, u8)] = &[
+	(EstablishedTimings::MODE_720X400_70, 28322, (720, 900, 18, 108), (400, 449, 12, 2), 0x1C),
+	(EstablishedTimings::MODE_720X400_88, 35500, (720, 900, 18, 108), (400, 449, 12, 2), 0x1C),
+	(EstablishedTimings::MODE_640X480_60, 25175, (640, 800, 16, 96), (480, 525, 10, 2), 0x18),
+	(EstablishedTimings::MODE_640X480_67, 30240, (640, 864, 64, 64), (480, 525, 3, 3), 0x18),
+	(EstablishedTimings::MODE_640X480_72, 31500, (640, 832, 24, 40), (480, 520, 9, 3), 0x18),
+	(EstablishedTimings::MODE_640X480_75, 31500, (640, 840, 16, 64), (480, 500, 1, 3), 0x18),
+	(EstablishedTimings::MODE_800X600_56, 36000, (800, 1024, 24, 72), (600, 625, 1, 2), 0x1E),
+	(EstablishedTimings::MODE_800X600_60, 40000, (800, 1056, 40, 128), (600, 628, 1, 4), 0x1E),
+	(EstablishedTimings::MODE_800X600_72, 50000, (800, 1040, 56, 120), (600, 666, 37, 6), 0x1E),
+	(EstablishedTimings::MODE_800X600_75, 49500, (800, 1056, 16, 80), (600, 625, 1, 3), 0x1E),
+	(EstablishedTimings::MODE_832X624_75, 57284, (832, 1152, 32, 64), (624, 667, 1, 3), 0x18),
+	// Interlaced, so the vertical values are per field
+	(EstablishedTimings::MODE_1024X768_87I, 44900, (1024, 1264, 8, 176), (384, 408, 0, 4), 0x9E),
+	(EstablishedTimings::MODE_1024X768_60, 65000, (1024, 1344, 24, 136), (768, 806, 3, 6), 0x18),
+	(EstablishedTimings::MODE_1024X768_70, 75000, (1024, 1328, 24, 136), (768, 806, 3, 6), 0x18),
+	(EstablishedTimings::MODE_1024X768_75, 78750, (1024, 1312, 16, 96), (768, 800, 1, 3), 0x1E),
+	(EstablishedTimings::MODE_1280X1024_75, 135000, (1280, 1688, 16, 144), (1024, 1066, 1, 3), 0x1E),
+	(EstablishedTimings::MODE_1152X870_75, 100000, (1152, 1456, 32, 128), (870, 915, 3, 3), 0x18),
+];
+
+impl EstablishedTimings {
+	/// Returns the timings of all the set modes. Manufacturer reserved bits are skipped.
+	pub fn modes(&self) -> Vec<DetailedTiming> {
+		ESTABLISHED_TIMINGS
+			.iter()
+			.filter(|&&(mode, ..)| self.contains(mode))
+			.map(|&(_, pixel_clock, horizontal, vertical, features)| {
+				mode_timing(pixel_clock, horizontal, vertical, features)
+			})
+			.collect()
+	}
+}
+
+fn encode_established_timing(timings: &EstablishedTimings, out: &mut Vec<u8>) {
+    out.extend_from_slice(&timings.bits().to_be_bytes()[1..]);
+}
+
+fn parse_established_timing(input: &[u8]) -> IResult<&[u8], EstablishedTimings> {
+    map(tuple((le_u8, le_u8, le_u8)), |(timings_i, timings_ii, manufacturer)| {
+        EstablishedTimings::from_bits_retain(
+            ((timings_i as u32) << 16) | ((timings_ii as u32) << 8) | manufacturer as u32,
+        )
+    })(input)
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AspectRatio {
+	/// Only used before EDID 1.3, where it replaces 16:10.
+	Ratio1_1,
+	Ratio16_10,
+	Ratio4_3,
+	Ratio5_4,
+	Ratio16_9,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct StandardTiming {
+	pub horizontal_active_pixels: u16,
+	/// Derived from the horizontal active pixels and the aspect ratio.
+	pub vertical_active_lines: u16,
+	pub aspect_ratio: AspectRatio,
+	/// Vertical refresh rate in Hz.
+	pub refresh_rate: u8,
+}
+
+fn standard_timing(header: &Header, x: u8, y: u8) -> Option<StandardTiming> {
+    if (x, y) == (0x01, 0x01) {
+        return None; // Unused slot
+    }
+
+    let horizontal_active_pixels = (x as u16 + 31) * 8;
+    let aspect_ratio = match y >> 6 {
+        0 if (header.version, header.revision) < (1, 3) => AspectRatio::Ratio1_1,
+        0 => AspectRatio::Ratio16_10,
+        1 => AspectRatio::Ratio4_3,
+        2 => AspectRatio::Ratio5_4,
+        _ => AspectRatio::Ratio16_9,
+    };
+    let vertical_active_lines = match aspect_ratio {
+        AspectRatio::Ratio1_1 => horizontal_active_pixels,
+        AspectRatio::Ratio16_10 => horizontal_active_pixels * 10 / 16,
+        AspectRatio::Ratio4_3 => horizontal_active_pixels * 3 / 4,
+        AspectRatio::Ratio5_4 => horizontal_active_pixels * 4 / 5,
+        AspectRatio::Ratio16_9 => horizontal_active_pixels * 9 / 16,
+    };
+
+    Some(StandardTiming {
+        horizontal_active_pixels,
+        vertical_active_lines,
+        aspect_ratio,
+        refresh_rate: (y & 0x3F) + 60,
+    })
+}
+
+fn encode_standard_timing(timing: &Option<StandardTiming>) -> [u8; 2] {
+    let timing = match *timing {
+        Some(ref timing) => timing,
+        None => return [0x01, 0x01],
+    };
+    let aspect_ratio = match timing.aspect_ratio {
+        AspectRatio::Ratio1_1 | AspectRatio::Ratio16_10 => 0,
+        AspectRatio::Ratio4_3 => 1,
+        AspectRatio::Ratio5_4 => 2,
+        AspectRatio::Ratio16_9 => 3,
+    };
+    [
+        (timing.horizontal_active_pixels / 8).wrapping_sub(31) as u8,
+        aspect_ratio << 6 | (timing.refresh_rate.wrapping_sub(60) & 0x3F),
+    ]
+}
+
+fn parse_standard_timings<'a, E: ParseError<&'a [u8]>>(
+    input: &'a [u8],
+    header: &Header,
+    n: usize,
+) -> IResult<&'a [u8], Vec<Option<StandardTiming>>, E> {
+    count(map(tuple((le_u8, le_u8)), |(x, y)| standard_timing(header, x, y)), n)(input)
+}
+
+fn parse_standard_timing<'a>(
+    input: &'a [u8],
+    header: &Header,
+) -> IResult<&'a [u8], [Option<StandardTiming>; 8]> {
+    map(
+        |i| parse_standard_timings(i, header, 8),
+        |timings| timings.try_into().unwrap(),
+    )(input)
 }
 
 // Function to parse descriptor text
@@ -115,6 +719,20 @@ fn parse_descriptor_text(input: &[u8]) -> IResult<&[u8], String> {
     )(input)
 }
 
+// Text is terminated by a line feed and padded with spaces when it is shorter than 13 bytes
+fn encode_descriptor_text(text: &str) -> [u8; 13] {
+    let mut data = [0x20; 13];
+    let mut len = 0;
+    for c in text.chars().take(13) {
+        data[len] = cp437::reverse(c).unwrap_or(b'?');
+        len += 1;
+    }
+    if len < data.len() {
+        data[len] = 0x0A;
+    }
+    data
+}
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct DetailedTiming {
 	/// Pixel clock in kHz.
@@ -138,6 +756,31 @@ pub struct DetailedTiming {
 	pub features: u8, /* TODO add enums etc. */
 }
 
+// Builds a timing without size or border from mode lines.
+fn mode_timing(
+    pixel_clock: u32,
+    (h_active, h_total, h_front_porch, h_sync): ModeLine,
+    (v_active, v_total, v_front_porch, v_sync): ModeLine,
+    features: u8,
+) -> DetailedTiming {
+    DetailedTiming {
+        pixel_clock,
+        horizontal_active_pixels: h_active,
+        horizontal_blanking_pixels: h_total - h_active,
+        vertical_active_lines: v_active,
+        vertical_blanking_lines: v_total - v_active,
+        horizontal_front_porch: h_front_porch,
+        horizontal_sync_width: h_sync,
+        vertical_front_porch: v_front_porch,
+        vertical_sync_width: v_sync,
+        horizontal_size: 0,
+        vertical_size: 0,
+        horizontal_border_pixels: 0,
+        vertical_border_pixels: 0,
+        features,
+    }
+}
+
 fn parse_detailed_timing(input: &[u8]) -> IResult<&[u8], DetailedTiming> {
     map(
         tuple((
@@ -192,9 +835,9 @@ fn parse_detailed_timing(input: &[u8]) -> IResult<&[u8], DetailedTiming> {
             horizontal_sync_width: (horizontal_sync_width_lo as u16)
                 | ((((porch_sync_hi >> 4) & 0x3) as u16) << 8),
             vertical_front_porch: ((vertical_lo >> 4) as u16)
-                | ((((porch_sync_hi >> 2) & 0x3) as u16) << 8),
+                | ((((porch_sync_hi >> 2) & 0x3) as u16) << 4),
             vertical_sync_width: ((vertical_lo & 0xf) as u16)
-                | (((porch_sync_hi & 0x3) as u16) << 8),
+                | (((porch_sync_hi & 0x3) as u16) << 4),
             horizontal_size: (horizontal_size_lo as u16) | (((size_hi >> 4) as u16) << 8),
             vertical_size: (vertical_size_lo as u16) | (((size_hi & 0xf) as u16) << 8),
             horizontal_border_pixels: horizontal_border,
@@ -204,51 +847,362 @@ fn parse_detailed_timing(input: &[u8]) -> IResult<&[u8], DetailedTiming> {
     )(input)
 }
 
+/// Encodes a detailed timing, returns `None` if one of its values doesn't fit in its bits. A zero
+/// pixel clock isn't allowed either, as it marks display descriptors.
+pub(crate) fn encode_detailed_timing(timing: &DetailedTiming, out: &mut Vec<u8>) -> Option<()> {
+    let fits = |v: u16, bits: u16| v >> bits == 0;
+    let pixel_clock = timing.pixel_clock / 10;
+    let valid = (1..=0xFFFF).contains(&pixel_clock)
+        && [
+            timing.horizontal_active_pixels,
+            timing.horizontal_blanking_pixels,
+            timing.vertical_active_lines,
+            timing.vertical_blanking_lines,
+            timing.horizontal_size,
+            timing.vertical_size,
+        ]
+        .iter()
+        .all(|&v| fits(v, 12))
+        && fits(timing.horizontal_front_porch, 10)
+        && fits(timing.horizontal_sync_width, 10)
+        && fits(timing.vertical_front_porch, 6)
+        && fits(timing.vertical_sync_width, 6);
+    if !valid {
+        return None;
+    }
+
+    let hi = |v: u16, bits: u16| ((v >> 8) & bits) as u8;
+    out.extend_from_slice(&(pixel_clock as u16).to_le_bytes());
+    out.extend_from_slice(&[
+        timing.horizontal_active_pixels as u8,
+        timing.horizontal_blanking_pixels as u8,
+        hi(timing.horizontal_active_pixels, 0xF) << 4 | hi(timing.horizontal_blanking_pixels, 0xF),
+        timing.vertical_active_lines as u8,
+        timing.vertical_blanking_lines as u8,
+        hi(timing.vertical_active_lines, 0xF) << 4 | hi(timing.vertical_blanking_lines, 0xF),
+        timing.horizontal_front_porch as u8,
+        timing.horizontal_sync_width as u8,
+        (timing.vertical_front_porch as u8 & 0xF) << 4 | (timing.vertical_sync_width as u8 & 0xF),
+        hi(timing.horizontal_front_porch, 0x3) << 6
+            | hi(timing.horizontal_sync_width, 0x3) << 4
+            | (((timing.vertical_front_porch >> 4) & 0x3) as u8) << 2
+            | ((timing.vertical_sync_width >> 4) & 0x3) as u8,
+        timing.horizontal_size as u8,
+        timing.vertical_size as u8,
+        hi(timing.horizontal_size, 0xF) << 4 | hi(timing.vertical_size, 0xF),
+        timing.horizontal_border_pixels,
+        timing.vertical_border_pixels,
+        timing.features,
+    ]);
+    Some(())
+}
+
+/// Offset added to the rates of a range limits descriptor, EDID 1.4 only.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum RateOffset {
+	None,
+	/// 255 is added to the maximum rate.
+	Max,
+	/// 255 is added to both the minimum and the maximum rates.
+	MinMax,
+	Reserved,
+}
+
+fn rate_offset(flags: u8) -> RateOffset {
+    match flags & 0x3 {
+        0 => RateOffset::None,
+        2 => RateOffset::Max,
+        3 => RateOffset::MinMax,
+        _ => RateOffset::Reserved,
+    }
+}
+
+/// Secondary GTF curve parameters.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct SecondaryGtf {
+	/// Start break frequency in kHz.
+	pub start_frequency: u16,
+	pub c: f32,
+	pub m: u16,
+	pub k: u8,
+	pub j: f32,
+}
+
+bitflags! {
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct CvtAspectRatios: u8 {
+		const RATIO_4_3 = 1 << 7;
+		const RATIO_16_9 = 1 << 6;
+		const RATIO_16_10 = 1 << 5;
+		const RATIO_5_4 = 1 << 4;
+		const RATIO_15_9 = 1 << 3;
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CvtAspectRatio {
+	Ratio4_3,
+	Ratio16_9,
+	Ratio16_10,
+	Ratio5_4,
+	Ratio15_9,
+	Reserved(u8),
+}
+
+bitflags! {
+	#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+	pub struct CvtScaling: u8 {
+		const HORIZONTAL_SHRINK = 1 << 7;
+		const HORIZONTAL_STRETCH = 1 << 6;
+		const VERTICAL_SHRINK = 1 << 5;
+		const VERTICAL_STRETCH = 1 << 4;
+	}
+}
+
+/// CVT support information.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct CvtSupport {
+	/// CVT standard version as (major, minor).
+	pub version: (u8, u8),
+	/// Extra pixel clock precision, in 0.25 MHz steps to subtract from the maximum pixel clock.
+	pub pixel_clock_precision: u8,
+	/// Maximum active pixels per line, `None` if there is no limit.
+	pub max_active_pixels: Option<u16>,
+	pub supported_aspect_ratios: CvtAspectRatios,
+	pub preferred_aspect_ratio: CvtAspectRatio,
+	/// CVT reduced blanking is supported.
+	pub reduced_blanking: bool,
+	/// CVT standard blanking is supported.
+	pub standard_blanking: bool,
+	pub scaling: CvtScaling,
+	/// Preferred vertical refresh rate in Hz.
+	pub preferred_refresh_rate: u8,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum VideoTimingSupport {
+	DefaultGtf,
+	/// No timing formula is supported, only the listed modes (EDID 1.4).
+	RangeLimitsOnly,
+	SecondaryGtf(SecondaryGtf),
+	Cvt(CvtSupport),
+	Reserved(u8),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct RangeLimits {
+	/// Minimum vertical rate in Hz.
+	pub min_vertical_rate: u16,
+	/// Maximum vertical rate in Hz.
+	pub max_vertical_rate: u16,
+	/// Minimum horizontal rate in kHz.
+	pub min_horizontal_rate: u16,
+	/// Maximum horizontal rate in kHz.
+	pub max_horizontal_rate: u16,
+	pub vertical_rate_offset: RateOffset,
+	pub horizontal_rate_offset: RateOffset,
+	/// Maximum pixel clock in MHz, as a multiple of 10 MHz.
+	pub max_pixel_clock: u16,
+	pub video_timing_support: VideoTimingSupport,
+}
+
+impl RangeLimits {
+	/// Maximum pixel clock in kHz, with the CVT extra precision applied.
+	pub fn max_pixel_clock_khz(&self) -> u32 {
+		let precision = match self.video_timing_support {
+			VideoTimingSupport::Cvt(ref cvt) => cvt.pixel_clock_precision as u32 * 250,
+			_ => 0,
+		};
+		(self.max_pixel_clock as u32 * 1000).saturating_sub(precision)
+	}
+}
+
+fn parse_secondary_gtf(input: &[u8]) -> IResult<&[u8], SecondaryGtf> {
+    map(
+        tuple((le_u8, le_u8, le_u8, le_u16, le_u8, le_u8)),
+        |(_reserved, start_frequency, c, m, k, j)| SecondaryGtf {
+            start_frequency: start_frequency as u16 * 2,
+            c: c as f32 / 2.0,
+            m,
+            k,
+            j: j as f32 / 2.0,
+        },
+    )(input)
+}
+
+fn parse_cvt_support(input: &[u8]) -> IResult<&[u8], CvtSupport> {
+    map(
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8, le_u8)),
+        |(version, precision_hi, max_active_lo, aspect_ratios, blanking, scaling, refresh_rate)| {
+            let max_active_pixels = (((precision_hi & 0x3) as u16) << 8 | max_active_lo as u16) * 8;
+            CvtSupport {
+                version: (version >> 4, version & 0xF),
+                pixel_clock_precision: precision_hi >> 2,
+                max_active_pixels: if max_active_pixels == 0 { None } else { Some(max_active_pixels) },
+                supported_aspect_ratios: CvtAspectRatios::from_bits_truncate(aspect_ratios),
+                preferred_aspect_ratio: match blanking >> 5 {
+                    0 => CvtAspectRatio::Ratio4_3,
+                    1 => CvtAspectRatio::Ratio16_9,
+                    2 => CvtAspectRatio::Ratio16_10,
+                    3 => CvtAspectRatio::Ratio5_4,
+                    4 => CvtAspectRatio::Ratio15_9,
+                    r => CvtAspectRatio::Reserved(r),
+                },
+                reduced_blanking: blanking & 0x10 != 0,
+                standard_blanking: blanking & 0x08 != 0,
+                scaling: CvtScaling::from_bits_truncate(scaling),
+                preferred_refresh_rate: refresh_rate,
+            }
+        },
+    )(input)
+}
+
+// Parses the 13 data bytes of a range limits descriptor, the offset flags are in the byte before
+fn parse_range_limits(input: &[u8], flags: u8) -> IResult<&[u8], RangeLimits> {
+    let (input, (min_vertical, max_vertical, min_horizontal, max_horizontal, max_pixel_clock, support)) =
+        tuple((le_u8, le_u8, le_u8, le_u8, le_u8, le_u8))(input)?;
+    let (input, video_timing_support) = match support {
+        0x00 => map(take(7_usize), |_| VideoTimingSupport::DefaultGtf)(input)?,
+        0x01 => map(take(7_usize), |_| VideoTimingSupport::RangeLimitsOnly)(input)?,
+        0x02 => map(parse_secondary_gtf, VideoTimingSupport::SecondaryGtf)(input)?,
+        0x04 => map(parse_cvt_support, VideoTimingSupport::Cvt)(input)?,
+        r => map(take(7_usize), |_| VideoTimingSupport::Reserved(r))(input)?,
+    };
+
+    let vertical_rate_offset = rate_offset(flags);
+    let horizontal_rate_offset = rate_offset(flags >> 2);
+    let offset = |rate: u8, offset: RateOffset, min: bool| match (offset, min) {
+        (RateOffset::MinMax, _) | (RateOffset::Max, false) => rate as u16 + 255,
+        _ => rate as u16,
+    };
+
+    Ok((
+        input,
+        RangeLimits {
+            min_vertical_rate: offset(min_vertical, vertical_rate_offset, true),
+            max_vertical_rate: offset(max_vertical, vertical_rate_offset, false),
+            min_horizontal_rate: offset(min_horizontal, horizontal_rate_offset, true),
+            max_horizontal_rate: offset(max_horizontal, horizontal_rate_offset, false),
+            vertical_rate_offset,
+            horizontal_rate_offset,
+            max_pixel_clock: max_pixel_clock as u16 * 10,
+            video_timing_support,
+        },
+    ))
+}
+
+fn encode_rate_offset(offset: RateOffset) -> u8 {
+    match offset {
+        RateOffset::None => 0,
+        RateOffset::Reserved => 1,
+        RateOffset::Max => 2,
+        RateOffset::MinMax => 3,
+    }
+}
+
+fn encode_cvt_support(cvt: &CvtSupport) -> [u8; 7] {
+    let max_active = cvt.max_active_pixels.unwrap_or(0) / 8;
+    let preferred_aspect_ratio = match cvt.preferred_aspect_ratio {
+        CvtAspectRatio::Ratio4_3 => 0,
+        CvtAspectRatio::Ratio16_9 => 1,
+        CvtAspectRatio::Ratio16_10 => 2,
+        CvtAspectRatio::Ratio5_4 => 3,
+        CvtAspectRatio::Ratio15_9 => 4,
+        CvtAspectRatio::Reserved(r) => r & 0x7,
+    };
+    [
+        cvt.version.0 << 4 | (cvt.version.1 & 0xF),
+        cvt.pixel_clock_precision << 2 | ((max_active >> 8) & 0x3) as u8,
+        max_active as u8,
+        cvt.supported_aspect_ratios.bits(),
+        preferred_aspect_ratio << 5 | (cvt.reduced_blanking as u8) << 4 | (cvt.standard_blanking as u8) << 3,
+        cvt.scaling.bits(),
+        cvt.preferred_refresh_rate,
+    ]
+}
+
+// Returns the offset flags and the 13 data bytes of a range limits descriptor
+fn encode_range_limits(limits: &RangeLimits) -> (u8, [u8; 13]) {
+    let offset = |rate: u16, offset: RateOffset, min: bool| match (offset, min) {
+        (RateOffset::MinMax, _) | (RateOffset::Max, false) => rate.wrapping_sub(255) as u8,
+        _ => rate as u8,
+    };
+    // Unused bytes are a line feed followed by spaces
+    let padding = [0x0A, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20];
+    let (support, parameters) = match limits.video_timing_support {
+        VideoTimingSupport::DefaultGtf => (0x00, padding),
+        VideoTimingSupport::RangeLimitsOnly => (0x01, padding),
+        VideoTimingSupport::SecondaryGtf(ref gtf) => {
+            let m = gtf.m.to_le_bytes();
+            let (c, j) = ((gtf.c * 2.0).round() as u8, (gtf.j * 2.0).round() as u8);
+            (0x02, [0x00, (gtf.start_frequency / 2) as u8, c, m[0], m[1], gtf.k, j])
+        }
+        VideoTimingSupport::Cvt(ref cvt) => (0x04, encode_cvt_support(cvt)),
+        VideoTimingSupport::Reserved(r) => (r, padding),
+    };
+
+    let mut data = [0; 13];
+    data[..6].copy_from_slice(&[
+        offset(limits.min_vertical_rate, limits.vertical_rate_offset, true),
+        offset(limits.max_vertical_rate, limits.vertical_rate_offset, false),
+        offset(limits.min_horizontal_rate, limits.horizontal_rate_offset, true),
+        offset(limits.max_horizontal_rate, limits.horizontal_rate_offset, false),
+        (limits.max_pixel_clock / 10) as u8,
+        support,
+    ]);
+    data[6..].copy_from_slice(&parameters);
+    let flags = encode_rate_offset(limits.horizontal_rate_offset) << 2 | encode_rate_offset(limits.vertical_rate_offset);
+    (flags, data)
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Descriptor {
 	DetailedTiming(DetailedTiming),
 	SerialNumber(String),
 	UnspecifiedText(String),
-	RangeLimits, // TODO
+	RangeLimits(RangeLimits),
 	ProductName(String),
-	WhitePoint, // TODO
-	StandardTiming, // TODO
-	ColorManagement,
-	TimingCodes,
-	EstablishedTimings,
+	/// Additional white point data, not decoded.
+	WhitePoint([u8; 13]),
+	StandardTiming([Option<StandardTiming>; 6]),
+	/// Color management data, not decoded.
+	ColorManagement([u8; 13]),
+	/// CVT 3-byte timing codes, not decoded.
+	TimingCodes([u8; 13]),
+	/// Established timings III, not decoded.
+	EstablishedTimings([u8; 13]),
 	Dummy,
-	Unknown([u8; 13]),
+	/// Descriptor with an unknown tag, e.g. a manufacturer-specified one, with its data.
+	Unknown(u8, [u8; 13]),
 }
 
 
 // Parser for Descriptor
-fn parse_descriptor(input: &[u8]) -> IResult<&[u8], Descriptor> {
+fn parse_descriptor<'a>(input: &'a [u8], header: &Header) -> IResult<&'a [u8], Descriptor> {
     let (input, descriptor_type) = peek(le_u16)(input)?;
 
     match descriptor_type {
         0 => {
-            // Descriptor block starts
-            let (input, _) = take(3_usize)(input)?;
-            let (input, descriptor) = preceded(
-                le_u8,
-                alt((
-                    map(preceded(le_u8, parse_descriptor_text), Descriptor::SerialNumber),
-                    map(preceded(le_u8, parse_descriptor_text), Descriptor::UnspecifiedText),
-                    map(preceded(le_u8, take(13_usize)), |_| Descriptor::RangeLimits),
-                    map(preceded(le_u8, parse_descriptor_text), Descriptor::ProductName),
-                    map(preceded(le_u8, take(13_usize)), |_| Descriptor::WhitePoint),
-                    map(preceded(le_u8, take(13_usize)), |_| Descriptor::StandardTiming),
-                    map(preceded(le_u8, take(13_usize)), |_| Descriptor::ColorManagement),
-                    map(preceded(le_u8, take(13_usize)), |_| Descriptor::TimingCodes),
-                    map(preceded(le_u8, take(13_usize)), |_| Descriptor::EstablishedTimings),
-                    map(preceded(le_u8, take(13_usize)), |_| Descriptor::Dummy),
-                    map(
-                        preceded(le_u8, count(le_u8, 13)),
-                        |data: Vec<u8>| Descriptor::Unknown(data.try_into().unwrap()),
-                    ),
-                )),
-            )(input)?;
-            Ok((input, descriptor))
+            // Display descriptor: zero pixel clock, reserved byte, tag, reserved byte and 13 bytes of data
+            let (input, (_, _, tag, flags)) = tuple((le_u16, le_u8, le_u8, le_u8))(input)?;
+            match tag {
+                0xFF => map(parse_descriptor_text, Descriptor::SerialNumber)(input),
+                0xFE => map(parse_descriptor_text, Descriptor::UnspecifiedText)(input),
+                0xFD => map(|i| parse_range_limits(i, flags), Descriptor::RangeLimits)(input),
+                0xFC => map(parse_descriptor_text, Descriptor::ProductName)(input),
+                0xFB => map(take(13_usize), |data: &[u8]| Descriptor::WhitePoint(data.try_into().unwrap()))(input),
+                0xFA => map(
+                    terminated(|i| parse_standard_timings(i, header, 6), le_u8),
+                    |timings| Descriptor::StandardTiming(timings.try_into().unwrap()),
+                )(input),
+                0xF9 => map(take(13_usize), |data: &[u8]| Descriptor::ColorManagement(data.try_into().unwrap()))(input),
+                0xF8 => map(take(13_usize), |data: &[u8]| Descriptor::TimingCodes(data.try_into().unwrap()))(input),
+                0xF7 => map(take(13_usize), |data: &[u8]| Descriptor::EstablishedTimings(data.try_into().unwrap()))(input),
+                0x10 => map(take(13_usize), |_| Descriptor::Dummy)(input),
+                _ => map(take(13_usize), |data: &[u8]| {
+                    Descriptor::Unknown(tag, data.try_into().unwrap())
+                })(input),
+            }
         }
         _ => {
             // Detailed timing block
@@ -257,24 +1211,175 @@ fn parse_descriptor(input: &[u8]) -> IResult<&[u8], Descriptor> {
     }
 }
 
+// Returns `None` if a detailed timing can't be encoded
+fn encode_descriptor(descriptor: &Descriptor, out: &mut Vec<u8>) -> Option<()> {
+    let (tag, flags, data) = match *descriptor {
+        Descriptor::DetailedTiming(ref timing) => return encode_detailed_timing(timing, out),
+        Descriptor::SerialNumber(ref text) => (0xFF, 0, encode_descriptor_text(text)),
+        Descriptor::UnspecifiedText(ref text) => (0xFE, 0, encode_descriptor_text(text)),
+        Descriptor::RangeLimits(ref limits) => {
+            let (flags, data) = encode_range_limits(limits);
+            (0xFD, flags, data)
+        }
+        Descriptor::ProductName(ref text) => (0xFC, 0, encode_descriptor_text(text)),
+        Descriptor::WhitePoint(data) => (0xFB, 0, data),
+        Descriptor::StandardTiming(ref timings) => {
+            let mut data = [0x0A; 13];
+            for (i, timing) in timings.iter().enumerate() {
+                data[i * 2..i * 2 + 2].copy_from_slice(&encode_standard_timing(timing));
+            }
+            (0xFA, 0, data)
+        }
+        Descriptor::ColorManagement(data) => (0xF9, 0, data),
+        Descriptor::TimingCodes(data) => (0xF8, 0, data),
+        Descriptor::EstablishedTimings(data) => (0xF7, 0, data),
+        Descriptor::Dummy => (0x10, 0, [0; 13]),
+        Descriptor::Unknown(tag, data) => (tag, 0, data),
+    };
+    out.extend_from_slice(&[0x00, 0x00, 0x00, tag, flags]);
+    out.extend_from_slice(&data);
+    Some(())
+}
+
+/// Extension block, with its raw 128 bytes if it isn't decoded.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Extension {
+	/// CTA-861 extension.
+	Cta(cta::CtaExtension),
+	/// VESA Video Timing Block extension.
+	VideoTimingBlock(Vec<u8>),
+	/// VESA Display Information extension.
+	DisplayInformation(Vec<u8>),
+	/// VESA Localized String extension.
+	LocalizedString(Vec<u8>),
+	/// Digital Packet Video Link extension.
+	Dpvl(Vec<u8>),
+	/// DisplayID extension, holding a single section.
+	DisplayId(displayid::DisplayId),
+	/// Block map, listing the tags of the other extension blocks.
+	BlockMap(Vec<u8>),
+	/// Extension defined by the display manufacturer.
+	Manufacturer(Vec<u8>),
+	Unknown(Vec<u8>),
+}
+
+impl Extension {
+	/// Returns the tag byte of the extension block, 0 for an empty unknown one.
+	pub fn tag(&self) -> u8 {
+		match *self {
+			Extension::Cta(_) => 0x02,
+			Extension::VideoTimingBlock(_) => 0x10,
+			Extension::DisplayInformation(_) => 0x40,
+			Extension::LocalizedString(_) => 0x50,
+			Extension::Dpvl(_) => 0x60,
+			Extension::DisplayId(_) => 0x70,
+			Extension::BlockMap(_) => 0xF0,
+			Extension::Manufacturer(_) => 0xFF,
+			Extension::Unknown(ref data) => data.first().copied().unwrap_or(0),
+		}
+	}
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct EDID {
 	pub header: Header,
 	pub display: Display,
-	chromaticity: (), // TODO
-	established_timing: (), // TODO
-	standard_timing: (), // TODO
+	pub chromaticity: Chromaticity,
+	pub established_timings: EstablishedTimings,
+	pub standard_timings: [Option<StandardTiming>; 8],
 	pub descriptors: Vec<Descriptor>,
+	pub extensions: Vec<Extension>,
 }
 
-pub fn parse_edid(input: &[u8]) -> IResult<&[u8], EDID, VerboseError<&[u8]>> {
+impl EDID {
+	/// Returns the preferred timing, which is the first detailed timing if the EDID has one.
+	pub fn preferred_timing(&self) -> Option<&DetailedTiming> {
+		let version = (self.header.version, self.header.revision);
+		if version < (1, 4) && !self.display.features.preferred_timing_mode {
+			return None;
+		}
+		match self.descriptors.first() {
+			Some(Descriptor::DetailedTiming(timing)) => Some(timing),
+			_ => None,
+		}
+	}
+
+	/// Replaces the preferred timing, or inserts it before the other descriptors if the first
+	/// one isn't a detailed timing. Inserting takes the place of a dummy descriptor when the
+	/// base block is full, and fails if it has none.
+	pub fn set_preferred_timing(&mut self, timing: DetailedTiming) -> Result<(), Error> {
+		self.display.features.preferred_timing_mode = true;
+		if let Some(Descriptor::DetailedTiming(preferred)) = self.descriptors.first_mut() {
+			*preferred = timing;
+			return Ok(());
+		}
+		if self.descriptors.len() >= 4 {
+			let dummy = self.descriptors.iter().rposition(|d| *d == Descriptor::Dummy);
+			self.descriptors.remove(dummy.ok_or(Error::BlockOverflow { block: 0 })?);
+		}
+		self.descriptors.insert(0, Descriptor::DetailedTiming(timing));
+		Ok(())
+	}
+
+	// Replaces the first descriptor this matches, or a dummy one, or adds it to the base block
+	fn set_descriptor(&mut self, descriptor: Descriptor, matches: fn(&Descriptor) -> bool) -> Result<(), Error> {
+		let slot = self.descriptors.iter().position(matches)
+			.or_else(|| self.descriptors.iter().position(|d| *d == Descriptor::Dummy));
+		match slot {
+			Some(i) => self.descriptors[i] = descriptor,
+			None if self.descriptors.len() < 4 => self.descriptors.push(descriptor),
+			None => return Err(Error::BlockOverflow { block: 0 }),
+		}
+		Ok(())
+	}
+
+	/// Sets the product name, in place of the current one or of a dummy descriptor. The name is
+	/// truncated to 13 characters when encoded.
+	pub fn set_product_name(&mut self, name: &str) -> Result<(), Error> {
+		self.set_descriptor(Descriptor::ProductName(name.to_string()), |d| matches!(d, Descriptor::ProductName(_)))
+	}
+
+	/// Sets the range limits, in place of the current ones or of a dummy descriptor.
+	pub fn set_range_limits(&mut self, limits: RangeLimits) -> Result<(), Error> {
+		self.set_descriptor(Descriptor::RangeLimits(limits), |d| matches!(d, Descriptor::RangeLimits(_)))
+	}
+
+	/// Removes the extension at this index, the extension count is updated when encoding.
+	pub fn remove_extension(&mut self, index: usize) -> Option<Extension> {
+		if index < self.extensions.len() {
+			Some(self.extensions.remove(index))
+		} else {
+			None
+		}
+	}
+
+	/// Removes the audio data blocks of all CTA-861 extensions, see `CtaExtension::remove_audio`.
+	pub fn remove_audio(&mut self) {
+		for extension in &mut self.extensions {
+			if let Extension::Cta(ref mut cta) = *extension {
+				cta.remove_audio();
+			}
+		}
+	}
+
+	/// Removes the HDR data blocks of all CTA-861 extensions, see `CtaExtension::remove_hdr`.
+	pub fn remove_hdr(&mut self) {
+		for extension in &mut self.extensions {
+			if let Extension::Cta(ref mut cta) = *extension {
+				cta.remove_hdr();
+			}
+		}
+	}
+}
+
+fn parse_edid(input: &[u8]) -> IResult<&[u8], EDID> {
     let (input, header) = parse_header(input)?;
-    let (input, display) = parse_display(input)?;
+    let (input, display) = parse_display(input, &header)?;
     let (input, chromaticity) = parse_chromaticity(input)?;
-    let (input, established_timing) = parse_established_timing(input)?;
-    let (input, standard_timing) = parse_standard_timing(input)?;
-    let (input, descriptors) = count(parse_descriptor, 4)(input).unwrap();
-    let (input, _) = take(1usize)(input)?; // Consume the extensions byte
+    let (input, established_timings) = parse_established_timing(input)?;
+    let (input, standard_timings) = parse_standard_timing(input, &header)?;
+    let (input, descriptors) = count(|i| parse_descriptor(i, &header), 4)(input)?;
+    let (input, _) = take(1usize)(input)?; // Consume the extensions byte, the blocks are parsed by the caller
     let (input, _) = take(1usize)(input)?; // Consume the checksum byte
 
     Ok((
@@ -283,134 +1388,646 @@ pub fn parse_edid(input: &[u8]) -> IResult<&[u8], EDID, VerboseError<&[u8]>> {
             header,
             display,
             chromaticity,
-            established_timing,
-            standard_timing,
+            established_timings,
+            standard_timings,
             descriptors,
+            extensions: Vec::new(),
         },
     ))
 }
 
-pub fn parse(data: &[u8]) -> nom::IResult<&[u8], EDID, VerboseError<&[u8]>> {
-	parse_edid(data)
-}
-
-
-//#[cfg(test)]
-//mod tests {
-//	use super::*;
-//
-//	fn test(d: &[u8], expected: &EDID) {
-//		match parse(d) {
-//			nom::IResult::Done(remaining, parsed) => {
-//				assert_eq!(remaining.len(), 0);
-//				assert_eq!(&parsed, expected);
-//			},
-//			nom::IResult::Error(err) => {
-//				panic!("{}", err);
-//			},
-//			nom::IResult::Incomplete(_) => {
-//				panic!("Incomplete");
-//			},
-//		}
-//	}
-//
-//	#[test]
-//	fn test_card0_vga_1() {
-//		let d = include_bytes!("../testdata/card0-VGA-1");
-//
-//		let expected = EDID{
-//			header: Header{
-//				vendor: ['S', 'A', 'M'],
-//				product: 596,
-//				serial: 1146106418,
-//				week: 27,
-//				year: 17,
-//				version: 1,
-//				revision: 3,
-//			},
-//			display: Display{
-//				video_input: 14,
-//				width: 47,
-//				height: 30,
-//				gamma: 120,
-//				features: 42,
-//			},
-//			chromaticity: (),
-//			established_timing: (),
-//			standard_timing: (),
-//			descriptors: vec!(
-//				Descriptor::DetailedTiming(DetailedTiming {
-//					pixel_clock: 146250,
-//					horizontal_active_pixels: 1680,
-//					horizontal_blanking_pixels: 560,
-//					vertical_active_lines: 1050,
-//					vertical_blanking_lines: 39,
-//					horizontal_front_porch: 104,
-//					horizontal_sync_width: 176,
-//					vertical_front_porch: 3,
-//					vertical_sync_width: 6,
-//					horizontal_size: 474,
-//					vertical_size: 296,
-//					horizontal_border_pixels: 0,
-//					vertical_border_pixels: 0,
-//					features: 28
-//				}),
-//				Descriptor::RangeLimits,
-//				Descriptor::ProductName("SyncMaster".to_string()),
-//				Descriptor::SerialNumber("HS3P701105".to_string()),
-//			),
-//		};
-//
-//		test(d, &expected);
-//	}
-//
-//	#[test]
-//	fn test_card0_edp_1() {
-//		let d = include_bytes!("../testdata/card0-eDP-1");
-//
-//		let expected = EDID{
-//			header: Header{
-//				vendor: ['S', 'H', 'P'],
-//				product: 5193,
-//				serial: 0,
-//				week: 32,
-//				year: 25,
-//				version: 1,
-//				revision: 4,
-//			},
-//			display: Display{
-//				video_input: 165,
-//				width: 29,
-//				height: 17,
-//				gamma: 120,
-//				features: 14,
-//			},
-//			chromaticity: (),
-//			established_timing: (),
-//			standard_timing: (),
-//			descriptors: vec!(
-//				Descriptor::DetailedTiming(DetailedTiming {
-//					pixel_clock: 138500,
-//					horizontal_active_pixels: 1920,
-//					horizontal_blanking_pixels: 160,
-//					vertical_active_lines: 1080,
-//					vertical_blanking_lines: 31,
-//					horizontal_front_porch: 48,
-//					horizontal_sync_width: 32,
-//					vertical_front_porch: 3,
-//					vertical_sync_width: 5,
-//					horizontal_size: 294,
-//					vertical_size: 165,
-//					horizontal_border_pixels: 0,
-//					vertical_border_pixels: 0,
-//					features: 24,
-//				}),
-//				Descriptor::Dummy,
-//				Descriptor::UnspecifiedText("DJCP6ÇLQ133M1".to_string()),
-//				Descriptor::Unknown([2, 65, 3, 40, 0, 18, 0, 0, 11, 1, 10, 32, 32]),
-//			),
-//		};
-//
-//		test(d, &expected);
-//	}
-//}
+// Returns the byte which makes the sum of the data and itself zero
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)).wrapping_neg()
+}
+
+fn verify_checksum(block: &[u8], index: usize) -> Result<(), Error> {
+    let expected = checksum(&block[..BLOCK_SIZE - 1]);
+    let actual = block[BLOCK_SIZE - 1];
+    if expected != actual {
+        return Err(Error::ChecksumMismatch { block: index, expected, actual });
+    }
+    Ok(())
+}
+
+fn parse_extension(block: &[u8]) -> IResult<&[u8], Extension> {
+    let data = block.to_vec();
+    let extension = match block[0] {
+        0x02 => return map(cta::parse_cta_extension, Extension::Cta)(block),
+        0x10 => Extension::VideoTimingBlock(data),
+        0x40 => Extension::DisplayInformation(data),
+        0x50 => Extension::LocalizedString(data),
+        0x60 => Extension::Dpvl(data),
+        0x70 => return map(displayid::parse_displayid_extension, Extension::DisplayId)(block),
+        0xF0 => Extension::BlockMap(data),
+        0xFF => Extension::Manufacturer(data),
+        _ => Extension::Unknown(data),
+    };
+    Ok((&block[block.len()..], extension))
+}
+
+// Returns the 128 bytes of an extension block, with its checksum, if its contents fit in it
+fn encode_extension(extension: &Extension) -> Option<Vec<u8>> {
+    let mut block = match *extension {
+        Extension::Cta(ref cta) => cta::encode_cta_extension(cta)?,
+        Extension::DisplayId(ref displayid) => displayid::encode_displayid_extension(displayid)?,
+        Extension::VideoTimingBlock(ref data)
+        | Extension::DisplayInformation(ref data)
+        | Extension::LocalizedString(ref data)
+        | Extension::Dpvl(ref data)
+        | Extension::BlockMap(ref data)
+        | Extension::Manufacturer(ref data)
+        | Extension::Unknown(ref data) => data.clone(),
+    };
+    if block.len() > BLOCK_SIZE {
+        return None;
+    }
+    block.resize(BLOCK_SIZE, 0);
+    block[BLOCK_SIZE - 1] = checksum(&block[..BLOCK_SIZE - 1]);
+    Some(block)
+}
+
+pub fn parse(data: &[u8]) -> Result<EDID, Error> {
+    if data.len() < BLOCK_SIZE {
+        return Err(Error::Truncated { expected: BLOCK_SIZE, actual: data.len() });
+    }
+    let block = &data[..BLOCK_SIZE];
+    if block[..HEADER.len()] != HEADER {
+        return Err(Error::InvalidHeader);
+    }
+    verify_checksum(block, 0)?;
+    let (version, revision) = (block[0x12], block[0x13]);
+    if version != 1 {
+        return Err(Error::UnsupportedVersion { version, revision });
+    }
+
+    let (week, year) = (block[0x10], block[0x11]);
+    if manufacture_date(week, year, version, revision).is_none() {
+        return Err(Error::InvalidManufactureDate { week, year });
+    }
+
+    let mut edid = match parse_edid(block) {
+        Ok((_, edid)) => edid,
+        Err(err) => {
+            // Once the header pattern and the date are known to be valid, only the descriptors of
+            // the complete block are left to fail
+            let rest = match err {
+                nom::Err::Error(err) | nom::Err::Failure(err) => err.input.len(),
+                nom::Err::Incomplete(_) => 0,
+            };
+            let index = (BLOCK_SIZE - rest).saturating_sub(DESCRIPTORS_OFFSET) / DESCRIPTOR_SIZE;
+            return Err(Error::MalformedDescriptor { offset: DESCRIPTORS_OFFSET + index.min(3) * DESCRIPTOR_SIZE });
+        }
+    };
+
+    let blocks = 1 + block[0x7E] as usize;
+    if data.len() < blocks * BLOCK_SIZE {
+        return Err(Error::Truncated { expected: blocks * BLOCK_SIZE, actual: data.len() });
+    }
+    for (index, block) in data[..blocks * BLOCK_SIZE].chunks(BLOCK_SIZE).enumerate().skip(1) {
+        verify_checksum(block, index)?;
+        let (_, extension) = parse_extension(block).map_err(|_| Error::MalformedExtension { block: index })?;
+        edid.extensions.push(extension);
+    }
+
+    Ok(edid)
+}
+
+/// Encodes an EDID into its base block followed by its extension blocks, with the extension
+/// count and the checksums computed from the contents. Missing descriptors are filled with dummy
+/// ones. What the parser doesn't keep, such as padding and reserved bits, is written as zeros or
+/// with the values the standards recommend. Values which don't fit in their fields, e.g. a pixel
+/// clock above 655.35 MHz in a detailed timing, are errors rather than truncated.
+pub fn encode(edid: &EDID) -> Result<Vec<u8>, Error> {
+    if edid.descriptors.len() > 4 || edid.extensions.len() > 0xFF {
+        return Err(Error::BlockOverflow { block: 0 });
+    }
+
+    let mut data = Vec::with_capacity(BLOCK_SIZE * (1 + edid.extensions.len()));
+    encode_header(&edid.header, &mut data);
+    encode_display(&edid.display, &mut data);
+    encode_chromaticity(&edid.chromaticity, &mut data);
+    encode_established_timing(&edid.established_timings, &mut data);
+    for timing in &edid.standard_timings {
+        data.extend_from_slice(&encode_standard_timing(timing));
+    }
+    for i in 0..4 {
+        encode_descriptor(edid.descriptors.get(i).unwrap_or(&Descriptor::Dummy), &mut data)
+            .ok_or(Error::BlockOverflow { block: 0 })?;
+    }
+    data.push(edid.extensions.len() as u8);
+    let sum = checksum(&data);
+    data.push(sum);
+
+    for (index, extension) in edid.extensions.iter().enumerate() {
+        let block = encode_extension(extension).ok_or(Error::BlockOverflow { block: index + 1 })?;
+        data.extend(block);
+    }
+    Ok(data)
+}
+
+/// Recomputes the checksums of raw EDID data after it was patched: those of the base block and
+/// of the extension blocks it counts, including the section checksums of DisplayID extensions.
+/// Blocks past the end of the data are ignored.
+pub fn fix_checksums(data: &mut [u8]) {
+    if data.len() < BLOCK_SIZE {
+        return;
+    }
+    let blocks = 1 + data[0x7E] as usize;
+    for (index, block) in data.chunks_exact_mut(BLOCK_SIZE).take(blocks).enumerate() {
+        if index > 0 && block[0] == 0x70 {
+            displayid::fix_extension_checksum(block);
+        }
+        block[BLOCK_SIZE - 1] = checksum(&block[..BLOCK_SIZE - 1]);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test(d: &[u8], expected: &EDID) {
+		let parsed = parse(d).unwrap();
+		assert_eq!(&parsed, expected);
+	}
+
+	fn point(x: u16, y: u16) -> ChromaticityPoint {
+		ChromaticityPoint {
+			x: x as f64 / 1024.0,
+			y: y as f64 / 1024.0,
+		}
+	}
+
+	fn standard(horizontal_active_pixels: u16, vertical_active_lines: u16, aspect_ratio: AspectRatio, refresh_rate: u8) -> Option<StandardTiming> {
+		Some(StandardTiming {
+			horizontal_active_pixels,
+			vertical_active_lines,
+			aspect_ratio,
+			refresh_rate,
+		})
+	}
+
+	#[test]
+	fn test_card0_vga_1() {
+		let d = include_bytes!("../testdata/card0-VGA-1");
+
+		let expected = EDID{
+			header: Header{
+				vendor: ['S', 'A', 'M'],
+				product: 596,
+				serial: 1146106418,
+				date: ManufactureDate::Manufactured { week: Some(27), year: 2007 },
+				version: 1,
+				revision: 3,
+			},
+			display: Display{
+				video_input: VideoInput::Analog(AnalogInput {
+					signal_level: SignalLevel::Level0700_0300,
+					setup: false,
+					separate_sync: true,
+					composite_sync: true,
+					sync_on_green: true,
+					serration: false,
+				}),
+				size: ScreenSize::Physical { width: 47, height: 30 },
+				gamma: Some(2.2),
+				features: Features {
+					dpms_standby: false,
+					dpms_suspend: false,
+					dpms_active_off: true,
+					color: ColorSupport::ColorType(DisplayColorType::Rgb),
+					srgb_default: false,
+					preferred_timing_mode: true,
+					continuous_frequency: false,
+				},
+			},
+			chromaticity: Chromaticity {
+				red: point(659, 341),
+				green: point(293, 617),
+				blue: point(156, 81),
+				white: point(321, 337),
+			},
+			established_timings: EstablishedTimings::from_bits_retain(0xBFEF80),
+			standard_timings: [
+				standard(1680, 1050, AspectRatio::Ratio16_10, 60),
+				standard(1280, 1024, AspectRatio::Ratio5_4, 60),
+				standard(1280, 960, AspectRatio::Ratio4_3, 60),
+				standard(1152, 864, AspectRatio::Ratio4_3, 75),
+				None,
+				None,
+				None,
+				None,
+			],
+			descriptors: vec!(
+				Descriptor::DetailedTiming(DetailedTiming {
+					pixel_clock: 146250,
+					horizontal_active_pixels: 1680,
+					horizontal_blanking_pixels: 560,
+					vertical_active_lines: 1050,
+					vertical_blanking_lines: 39,
+					horizontal_front_porch: 104,
+					horizontal_sync_width: 176,
+					vertical_front_porch: 3,
+					vertical_sync_width: 6,
+					horizontal_size: 474,
+					vertical_size: 296,
+					horizontal_border_pixels: 0,
+					vertical_border_pixels: 0,
+					features: 28
+				}),
+				Descriptor::RangeLimits(RangeLimits {
+					min_vertical_rate: 56,
+					max_vertical_rate: 75,
+					min_horizontal_rate: 30,
+					max_horizontal_rate: 81,
+					vertical_rate_offset: RateOffset::None,
+					horizontal_rate_offset: RateOffset::None,
+					max_pixel_clock: 170,
+					video_timing_support: VideoTimingSupport::DefaultGtf,
+				}),
+				Descriptor::ProductName("SyncMaster".to_string()),
+				Descriptor::SerialNumber("HS3P701105".to_string()),
+			),
+			extensions: vec![],
+		};
+
+		test(d, &expected);
+	}
+
+	#[test]
+	fn test_card0_edp_1() {
+		let d = include_bytes!("../testdata/card0-eDP-1");
+
+		let expected = EDID{
+			header: Header{
+				vendor: ['S', 'H', 'P'],
+				product: 5193,
+				serial: 0,
+				date: ManufactureDate::Manufactured { week: Some(32), year: 2015 },
+				version: 1,
+				revision: 4,
+			},
+			display: Display{
+				video_input: VideoInput::Digital(DigitalInput {
+					color_bit_depth: Some(8),
+					interface: DigitalInterface::DisplayPort,
+				}),
+				size: ScreenSize::Physical { width: 29, height: 17 },
+				gamma: Some(2.2),
+				features: Features {
+					dpms_standby: false,
+					dpms_suspend: false,
+					dpms_active_off: false,
+					color: ColorSupport::Encodings(ColorEncodings::YCBCR_444),
+					srgb_default: true,
+					preferred_timing_mode: true,
+					continuous_frequency: false,
+				},
+			},
+			chromaticity: Chromaticity {
+				red: point(655, 337),
+				green: point(307, 614),
+				blue: point(153, 61),
+				white: point(320, 336),
+			},
+			established_timings: EstablishedTimings::empty(),
+			standard_timings: [None; 8],
+			descriptors: vec!(
+				Descriptor::DetailedTiming(DetailedTiming {
+					pixel_clock: 138500,
+					horizontal_active_pixels: 1920,
+					horizontal_blanking_pixels: 160,
+					vertical_active_lines: 1080,
+					vertical_blanking_lines: 31,
+					horizontal_front_porch: 48,
+					horizontal_sync_width: 32,
+					vertical_front_porch: 3,
+					vertical_sync_width: 5,
+					horizontal_size: 294,
+					vertical_size: 165,
+					horizontal_border_pixels: 0,
+					vertical_border_pixels: 0,
+					features: 24,
+				}),
+				Descriptor::Dummy,
+				Descriptor::UnspecifiedText("DJCP6ÇLQ133M1".to_string()),
+				Descriptor::Unknown(0x00, [2, 65, 3, 40, 0, 18, 0, 0, 11, 1, 10, 32, 32]),
+			),
+			extensions: vec![],
+		};
+
+		test(d, &expected);
+	}
+
+	#[test]
+	fn test_chromaticity() {
+		// The 2 low bits of each coordinate are packed into the first two bytes
+		let d = [0xD8, 0x1B, 0xA4, 0x57, 0x4C, 0x9A, 0x26, 0x0F, 0x50, 0x54];
+		let chromaticity = parse_chromaticity(&d).unwrap().1;
+		assert_eq!(chromaticity, Chromaticity {
+			red: point(659, 349),
+			green: point(306, 616),
+			blue: point(152, 61),
+			white: point(322, 339),
+		});
+		assert!((chromaticity.white.x - 0.3145).abs() < 0.0001);
+		assert!((chromaticity.white.y - 0.3311).abs() < 0.0001);
+	}
+
+	#[test]
+	fn test_established_timings() {
+		let timings = parse_established_timing(&[0x21, 0x08, 0x81]).unwrap().1;
+		assert_eq!(
+			timings,
+			EstablishedTimings::MODE_640X480_60
+				| EstablishedTimings::MODE_800X600_60
+				| EstablishedTimings::MODE_1024X768_60
+				| EstablishedTimings::MODE_1152X870_75
+				| EstablishedTimings::MANUFACTURER_RESERVED_0
+		);
+
+		// Manufacturer reserved bits have no mode
+		let modes: Vec<_> = timings.modes().iter().map(|t| (t.horizontal_active_pixels, t.vertical_active_lines)).collect();
+		assert_eq!(modes, vec![(640, 480), (800, 600), (1024, 768), (1152, 870)]);
+	}
+
+	#[test]
+	fn test_standard_timing_aspect_ratio() {
+		let header = |revision| Header {
+			vendor: ['S', 'A', 'M'],
+			product: 0,
+			serial: 0,
+			date: ManufactureDate::Manufactured { week: None, year: 2007 },
+			version: 1,
+			revision,
+		};
+
+		// Aspect ratio bits 00 mean 1:1 before EDID 1.3, and 16:10 starting with it
+		assert_eq!(standard_timing(&header(2), 0x81, 0x00), standard(1280, 1280, AspectRatio::Ratio1_1, 60));
+		assert_eq!(standard_timing(&header(3), 0x81, 0x00), standard(1280, 800, AspectRatio::Ratio16_10, 60));
+		assert_eq!(standard_timing(&header(3), 0x81, 0x4F), standard(1280, 960, AspectRatio::Ratio4_3, 75));
+		assert_eq!(standard_timing(&header(3), 0x81, 0x80), standard(1280, 1024, AspectRatio::Ratio5_4, 60));
+		assert_eq!(standard_timing(&header(4), 0xD1, 0xC0), standard(1920, 1080, AspectRatio::Ratio16_9, 60));
+		assert_eq!(standard_timing(&header(4), 0x01, 0x01), None);
+	}
+
+	#[test]
+	fn test_detailed_timing_porches() {
+		// 1920x1080 with a vertical front porch of 20 lines and a sync width of 18 lines, whose
+		// high bits are in the last nibble of the porch and sync byte
+		let d = [
+			0x02, 0x3A, 0x80, 0x18, 0x71, 0x38, 0x2D, 0x40, 0x58, 0x2C, 0x42, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x1E,
+		];
+		let timing = parse_detailed_timing(&d).unwrap().1;
+		assert_eq!((timing.horizontal_front_porch, timing.horizontal_sync_width), (88, 44));
+		assert_eq!((timing.vertical_front_porch, timing.vertical_sync_width), (20, 18));
+	}
+
+	#[test]
+	fn test_range_limits_cvt() {
+		let header = parse_header(include_bytes!("../testdata/card0-eDP-1")).unwrap().1;
+		let d = [
+			0x00, 0x00, 0x00, 0xFD, 0x02, 0x30, 0x41, 0x1E, 0xFF, 0x3C, 0x04,
+			0x11, 0x0D, 0x40, 0xE0, 0x38, 0xF0, 0x3C,
+		];
+
+		let expected = Descriptor::RangeLimits(RangeLimits {
+			min_vertical_rate: 48,
+			max_vertical_rate: 320,
+			min_horizontal_rate: 30,
+			max_horizontal_rate: 255,
+			vertical_rate_offset: RateOffset::Max,
+			horizontal_rate_offset: RateOffset::None,
+			max_pixel_clock: 600,
+			video_timing_support: VideoTimingSupport::Cvt(CvtSupport {
+				version: (1, 1),
+				pixel_clock_precision: 3,
+				max_active_pixels: Some(2560),
+				supported_aspect_ratios: CvtAspectRatios::RATIO_4_3 | CvtAspectRatios::RATIO_16_9 | CvtAspectRatios::RATIO_16_10,
+				preferred_aspect_ratio: CvtAspectRatio::Ratio16_9,
+				reduced_blanking: true,
+				standard_blanking: true,
+				scaling: CvtScaling::all(),
+				preferred_refresh_rate: 60,
+			}),
+		});
+
+		let (remaining, parsed) = parse_descriptor(&d, &header).unwrap();
+		assert_eq!(remaining.len(), 0);
+		assert_eq!(parsed, expected);
+		if let Descriptor::RangeLimits(limits) = parsed {
+			assert_eq!(limits.max_pixel_clock_khz(), 599250);
+		}
+	}
+
+	#[test]
+	fn test_video_input_legacy_digital() {
+		let mut header = parse_header(include_bytes!("../testdata/card0-eDP-1")).unwrap().1;
+		header.revision = 3;
+		assert_eq!(video_input(&header, 0x81), VideoInput::LegacyDigital { dfp1_compatible: true });
+	}
+
+	#[test]
+	fn test_preferred_timing() {
+		let edid = parse(include_bytes!("../testdata/card0-VGA-1")).unwrap();
+		assert_eq!(edid.preferred_timing().map(|t| t.pixel_clock), Some(146250));
+	}
+
+	#[test]
+	fn test_screen_size_aspect_ratio() {
+		let header = parse_header(include_bytes!("../testdata/card0-eDP-1")).unwrap().1;
+		assert_eq!(screen_size(&header, 79, 0), ScreenSize::LandscapeAspectRatio(1.78));
+		assert_eq!(screen_size(&header, 0, 79), ScreenSize::PortraitAspectRatio(100.0 / 178.0));
+		assert_eq!(screen_size(&header, 0, 0), ScreenSize::Undefined);
+	}
+
+	#[test]
+	fn test_manufacture_date() {
+		assert_eq!(manufacture_date(0xFF, 0x1B, 1, 4), Some(ManufactureDate::ModelYear(2017)));
+		assert_eq!(manufacture_date(0, 0x1B, 1, 4), Some(ManufactureDate::Manufactured { week: None, year: 2017 }));
+		assert_eq!(manufacture_date(0xFF, 0x1B, 1, 3), Some(ManufactureDate::Manufactured { week: None, year: 2017 }));
+		assert_eq!(manufacture_date(60, 0x1B, 1, 4), Some(ManufactureDate::Manufactured { week: None, year: 2017 }));
+		assert_eq!(manufacture_date(1, 0x0F, 1, 4), None);
+	}
+
+	#[test]
+	fn test_errors() {
+		let d = include_bytes!("../testdata/card0-VGA-1");
+
+		assert_eq!(parse(&d[..100]), Err(Error::Truncated { expected: 128, actual: 100 }));
+
+		let mut bad_header = *d;
+		bad_header[0] = 0x01;
+		assert_eq!(parse(&bad_header), Err(Error::InvalidHeader));
+
+		let mut bad_checksum = *d;
+		bad_checksum[127] = 0x00;
+		assert_eq!(parse(&bad_checksum), Err(Error::ChecksumMismatch { block: 0, expected: 0xDA, actual: 0x00 }));
+
+		// A week out of range is unspecified, a year before 2006 in EDID 1.4 is an error
+		let mut bad_week = *d;
+		bad_week[0x10] = 60;
+		bad_week[127] = checksum(&bad_week[..127]);
+		assert_eq!(parse(&bad_week).unwrap().header.date, ManufactureDate::Manufactured { week: None, year: 2007 });
+
+		let mut bad_year = *d;
+		bad_year[0x11] = 0x0F;
+		bad_year[0x13] = 4;
+		bad_year[127] = checksum(&bad_year[..127]);
+		assert_eq!(parse(&bad_year), Err(Error::InvalidManufactureDate { week: 27, year: 15 }));
+	}
+
+	#[test]
+	fn test_extensions() {
+		let mut d = include_bytes!("../testdata/card0-eDP-1").to_vec();
+		d[0x7E] = 2;
+		d[0x7F] = d[0x7F].wrapping_sub(2);
+		let mut extension = [0u8; 128];
+		extension[0] = 0x42;
+		extension[127] = 0xBE;
+		d.extend_from_slice(&extension);
+
+		assert_eq!(parse(&d), Err(Error::Truncated { expected: 384, actual: 256 }));
+
+		d.extend_from_slice(&extension);
+		d[0x100] = 0x60;
+		assert_eq!(parse(&d), Err(Error::ChecksumMismatch { block: 2, expected: 0xA0, actual: 0xBE }));
+
+		d[0x17F] = 0xA0;
+		let edid = parse(&d).unwrap();
+		assert_eq!(edid.extensions, vec![Extension::Unknown(d[0x80..0x100].to_vec()), Extension::Dpvl(d[0x100..].to_vec())]);
+		assert_eq!(edid.extensions.iter().map(Extension::tag).collect::<Vec<_>>(), vec![0x42, 0x60]);
+		assert_eq!(Extension::Unknown(vec![]).tag(), 0);
+
+		// An empty DisplayID section, but with an unsupported version
+		d[0x100] = 0x70;
+		d[0x17F] = 0x90;
+		assert_eq!(parse(&d), Err(Error::MalformedExtension { block: 2 }));
+		d[0x101] = 0x12;
+		d[0x105] = 0xEE;
+		let edid = parse(&d).unwrap();
+		assert_eq!(edid.extensions[1].tag(), 0x70);
+
+		// A Type I timing block cut short doesn't spoil the rest of the EDID
+		d[0x102] = 13;
+		d[0x105..0x112].copy_from_slice(&[0x03, 0x00, 0x0A, 0x01, 0x3A, 0x00, 0x84, 0x7F, 0x07, 0x17, 0x01, 0x57, 0x80]);
+		fix_checksums(&mut d);
+		let edid = parse(&d).unwrap();
+		assert_eq!(edid.descriptors, parse(include_bytes!("../testdata/card0-eDP-1")).unwrap().descriptors);
+		match edid.extensions[1] {
+			Extension::DisplayId(ref displayid) => {
+				assert_eq!(displayid.data_blocks, vec![displayid::DataBlock::Unknown(0x03, 0x00, d[0x108..0x112].to_vec())]);
+			}
+			ref e => panic!("unexpected extension {:?}", e),
+		}
+	}
+
+	#[test]
+	fn test_encode() {
+		for d in [
+			&include_bytes!("../testdata/card0-VGA-1")[..],
+			&include_bytes!("../testdata/card0-eDP-1")[..],
+			&include_bytes!("../testdata/card0-LVDS-1")[..],
+		] {
+			assert_eq!(encode(&parse(d).unwrap()).unwrap(), d);
+		}
+
+		// Descriptors which aren't decoded keep their data
+		let mut d = include_bytes!("../testdata/card0-eDP-1").to_vec();
+		for (i, tag) in [0xFB, 0xF9, 0xF8, 0xF7].iter().enumerate() {
+			let offset = DESCRIPTORS_OFFSET + i * DESCRIPTOR_SIZE;
+			d[offset..offset + 18].copy_from_slice(&[0; 18]);
+			d[offset + 3] = *tag;
+			d[offset + 5..offset + 18].copy_from_slice(&[0x10 + i as u8; 13]);
+		}
+		fix_checksums(&mut d);
+		let edid = parse(&d).unwrap();
+		assert_eq!(edid.descriptors[3], Descriptor::EstablishedTimings([0x13; 13]));
+		assert_eq!(encode(&edid).unwrap(), d);
+
+		let mut edid = parse(include_bytes!("../testdata/card0-eDP-1")).unwrap();
+		edid.extensions.push(Extension::Unknown(vec![0x42; 129]));
+		assert_eq!(encode(&edid), Err(Error::BlockOverflow { block: 1 }));
+
+		edid.extensions[0] = Extension::Unknown(vec![0x42]);
+		let d = encode(&edid).unwrap();
+		assert_eq!(d.len(), 256);
+		assert_eq!(parse(&d).unwrap().extensions[0].tag(), 0x42);
+
+		edid.descriptors.resize(5, Descriptor::Dummy);
+		assert_eq!(encode(&edid), Err(Error::BlockOverflow { block: 0 }));
+
+		// Detailed timings whose values don't fit in their fields
+		let mut edid = parse(include_bytes!("../testdata/card0-eDP-1")).unwrap();
+		let timing = *edid.preferred_timing().unwrap();
+		for timing in [
+			DetailedTiming { pixel_clock: 1_097_750, ..timing },
+			DetailedTiming { horizontal_active_pixels: 4096, ..timing },
+			DetailedTiming { horizontal_front_porch: 1024, ..timing },
+			DetailedTiming { vertical_sync_width: 64, ..timing },
+		] {
+			edid.descriptors[0] = Descriptor::DetailedTiming(timing);
+			assert_eq!(encode(&edid), Err(Error::BlockOverflow { block: 0 }));
+		}
+	}
+
+	#[test]
+	fn test_edit() {
+		let range_limits = |edid: &EDID| {
+			edid.descriptors.iter().find_map(|d| match *d {
+				Descriptor::RangeLimits(ref limits) => Some(*limits),
+				_ => None,
+			}).unwrap()
+		};
+
+		let mut edid = parse(include_bytes!("../testdata/card0-VGA-1")).unwrap();
+		let timing = cvt_timing(1280, 800, 60.0, true);
+		let mut limits = range_limits(&edid);
+		limits.max_pixel_clock = 200;
+		edid.set_preferred_timing(timing).unwrap();
+		edid.set_product_name("Patched").unwrap();
+		edid.set_range_limits(limits).unwrap();
+		let edid = parse(&encode(&edid).unwrap()).unwrap();
+		assert_eq!(edid.preferred_timing(), Some(&timing));
+		assert!(edid.descriptors.contains(&Descriptor::ProductName("Patched".to_string())));
+		assert_eq!(range_limits(&edid), limits);
+
+		let mut edid = EdidBuilder::new()
+			.vics(&[16])
+			.audio(cta::ShortAudioDescriptor {
+				format: cta::AudioFormat::Lpcm,
+				max_channels: 2,
+				sample_rates: cta::SampleRates::all(),
+				detail: cta::AudioFormatDetail::BitDepths(cta::BitDepths::all()),
+			})
+			.hdr_static_metadata(cta::HdrStaticMetadata {
+				eotfs: cta::Eotfs::TRADITIONAL_SDR | cta::Eotfs::SMPTE_ST_2084,
+				descriptors: cta::StaticMetadataDescriptors::TYPE_1,
+				max_luminance: None,
+				max_frame_average_luminance: None,
+				min_luminance: None,
+			})
+			.edid();
+		edid.remove_audio();
+		edid.remove_hdr();
+		match edid.extensions[0] {
+			Extension::Cta(ref cta) => {
+				assert!(!cta.basic_audio);
+				assert_eq!(cta.data_blocks.len(), 1);
+			}
+			ref e => panic!("unexpected extension {:?}", e),
+		}
+		assert!(edid.remove_extension(0).is_some());
+		assert_eq!(edid.remove_extension(0), None);
+
+		// Patching the range limits of the raw data, then its checksum
+		let mut d = include_bytes!("../testdata/card0-VGA-1").to_vec();
+		let i = (0..4).map(|i| DESCRIPTORS_OFFSET + i * DESCRIPTOR_SIZE).find(|&i| d[i + 3] == 0xFD).unwrap();
+		d[i + 5] = 48;
+		assert!(parse(&d).is_err());
+		fix_checksums(&mut d);
+		assert_eq!(range_limits(&parse(&d).unwrap()).min_vertical_rate, 48);
+	}
+}